@@ -0,0 +1,212 @@
+use {
+	axum::{
+		extract::{ConnectInfo, State},
+		http::{header::RETRY_AFTER, HeaderValue, Method, Request, StatusCode},
+		middleware::Next,
+		response::{IntoResponse, Response},
+		Json,
+	},
+	color_eyre::Result as Eyre,
+	database::{crd::read::get_api_key, schemas::ApiKeyRow},
+	log::{debug, error},
+	sqlx::{MySql, Pool},
+	std::{
+		collections::HashMap,
+		net::{IpAddr, SocketAddr},
+		sync::{Arc, Mutex},
+		time::{Duration, Instant},
+	},
+};
+
+pub(crate) const API_KEY_HEADER: &str = "x-schnose-auth-key";
+
+/// Quotas are counted in fixed windows of this length.
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// How long a looked up key is trusted before asking the database again.
+const KEY_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Whoever sent the current request. Available to every handler as an `Extension`.
+#[derive(Debug, Clone)]
+pub(crate) enum Caller {
+	Anonymous(IpAddr),
+	Key(ApiKeyRow),
+}
+
+impl Caller {
+	pub(crate) fn has_scope(&self, scope: &str) -> bool {
+		match self {
+			Self::Anonymous(_) => scope == "read",
+			Self::Key(api_key) => api_key.has_scope(scope),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Bucket {
+	Ip(IpAddr),
+	Key(String),
+}
+
+pub(crate) struct Auth {
+	pool: Pool<MySql>,
+	anonymous_limit: u32,
+	keys: Mutex<HashMap<String, (Option<ApiKeyRow>, Instant)>>,
+	windows: Mutex<HashMap<Bucket, (Instant, u32)>>,
+}
+
+impl Auth {
+	pub(crate) fn new(pool: Pool<MySql>, anonymous_limit: u32) -> Arc<Self> {
+		Arc::new(Self {
+			pool,
+			anonymous_limit,
+			keys: Mutex::new(HashMap::new()),
+			windows: Mutex::new(HashMap::new()),
+		})
+	}
+
+	/// The cached result of looking up `api_key`, if it's still fresh.
+	fn cached(&self, api_key: &str) -> Option<Option<ApiKeyRow>> {
+		let keys = self.keys.lock().unwrap();
+		let (row, fetched_on) = keys.get(api_key)?;
+		(fetched_on.elapsed() < KEY_CACHE_TTL).then(|| row.clone())
+	}
+
+	/// Looks up `api_key` in the database and caches the result.
+	async fn lookup(&self, api_key: &str) -> Eyre<Option<ApiKeyRow>> {
+		// unknown keys get cached as well, so guessing keys doesn't hit the database every time
+		let row = match get_api_key(api_key, &self.pool).await {
+			Ok(row) => Some(row),
+			Err(why) if matches!(why.downcast_ref(), Some(sqlx::Error::RowNotFound)) => None,
+			Err(why) => return Err(why),
+		};
+
+		let now = Instant::now();
+		let mut keys = self.keys.lock().unwrap();
+
+		if keys.len() > 10_000 {
+			keys.retain(|_, (_, fetched_on)| now.duration_since(*fetched_on) < KEY_CACHE_TTL);
+		}
+
+		keys.insert(api_key.to_owned(), (row.clone(), now));
+
+		Ok(row)
+	}
+
+	/// Counts a request against a bucket. Returns the remaining quota, or how long the caller
+	/// has to wait if there is none left.
+	fn hit(&self, bucket: Bucket, limit: u32) -> Result<u32, Duration> {
+		let now = Instant::now();
+		let mut windows = self.windows.lock().unwrap();
+
+		if windows.len() > 10_000 {
+			windows.retain(|_, (start, _)| now.duration_since(*start) < WINDOW);
+		}
+
+		let (start, count) = windows
+			.entry(bucket)
+			.or_insert((now, 0));
+
+		if now.duration_since(*start) >= WINDOW {
+			*start = now;
+			*count = 0;
+		}
+
+		if *count >= limit {
+			return Err(WINDOW - now.duration_since(*start));
+		}
+
+		*count += 1;
+		Ok(limit - *count)
+	}
+}
+
+pub(crate) async fn middleware<B>(
+	State(auth): State<Arc<Auth>>,
+	ConnectInfo(addr): ConnectInfo<SocketAddr>,
+	mut request: Request<B>,
+	next: Next<B>,
+) -> Response {
+	let caller = match request.headers().get(API_KEY_HEADER) {
+		None => Caller::Anonymous(addr.ip()),
+		Some(api_key) => {
+			let limit = auth.anonymous_limit;
+
+			// database lookups and failed lookups count towards the IP's quota, so keys can't be
+			// guessed without limits
+			let (api_key, counted) = match api_key
+				.to_str()
+				.map(|api_key| (api_key, auth.cached(api_key)))
+			{
+				Ok((_, Some(row))) => (row, false),
+				Ok((api_key, None)) => {
+					if let Err(retry_after) = auth.hit(Bucket::Ip(addr.ip()), limit) {
+						return too_many_requests(limit, retry_after);
+					}
+
+					match auth.lookup(api_key).await {
+						Ok(row) => (row, true),
+						Err(why) => {
+							error!("Failed to look up API key: {why:?}");
+							return (
+								StatusCode::INTERNAL_SERVER_ERROR,
+								Json("Failed to look up API key."),
+							)
+								.into_response();
+						}
+					}
+				}
+				Err(_) => (None, false),
+			};
+
+			match api_key {
+				Some(api_key) => Caller::Key(api_key),
+				None => {
+					if !counted {
+						if let Err(retry_after) = auth.hit(Bucket::Ip(addr.ip()), limit) {
+							return too_many_requests(limit, retry_after);
+						}
+					}
+
+					return (StatusCode::UNAUTHORIZED, Json("Invalid API key.")).into_response();
+				}
+			}
+		}
+	};
+
+	debug!("Caller: {caller:?}");
+
+	if request.method() == Method::GET && !caller.has_scope("read") {
+		return (StatusCode::FORBIDDEN, Json("API key is missing the `read` scope."))
+			.into_response();
+	}
+
+	let (bucket, limit) = match &caller {
+		Caller::Anonymous(ip) => (Bucket::Ip(*ip), auth.anonymous_limit),
+		Caller::Key(api_key) => (Bucket::Key(api_key.api_key.clone()), api_key.requests_per_minute),
+	};
+
+	let remaining = match auth.hit(bucket, limit) {
+		Ok(remaining) => remaining,
+		Err(retry_after) => return too_many_requests(limit, retry_after),
+	};
+
+	request.extensions_mut().insert(caller);
+
+	let mut response = next.run(request).await;
+	let headers = response.headers_mut();
+	headers.insert("x-ratelimit-limit", HeaderValue::from(limit));
+	headers.insert("x-ratelimit-remaining", HeaderValue::from(remaining));
+
+	response
+}
+
+fn too_many_requests(limit: u32, retry_after: Duration) -> Response {
+	let retry_after = (retry_after.as_millis() as u64).div_ceil(1000);
+	(
+		StatusCode::TOO_MANY_REQUESTS,
+		[(RETRY_AFTER, retry_after.to_string())],
+		Json(format!("Rate limit of {limit} requests per minute exceeded.")),
+	)
+		.into_response()
+}
@@ -0,0 +1,90 @@
+use crate::Error;
+
+/// Encodes the sort key of the last row of a page into an opaque cursor.
+pub(crate) fn encode(keys: &[&str]) -> String {
+	keys.join("\n")
+		.bytes()
+		.map(|byte| format!("{byte:02x}"))
+		.collect()
+}
+
+/// Decodes a cursor created by [`encode`] back into `N` sort keys.
+pub(crate) fn decode<const N: usize>(cursor: &str) -> Result<[String; N], Error> {
+	let invalid = || invalid(cursor);
+
+	if !cursor.len().is_multiple_of(2) {
+		return Err(invalid());
+	}
+
+	let bytes = (0..cursor.len())
+		.step_by(2)
+		.map(|i| {
+			cursor
+				.get(i..i + 2)
+				.and_then(|byte| u8::from_str_radix(byte, 16).ok())
+		})
+		.collect::<Option<Vec<u8>>>()
+		.ok_or_else(invalid)?;
+
+	let keys = String::from_utf8(bytes).map_err(|_| invalid())?;
+
+	keys.split('\n')
+		.map(String::from)
+		.collect::<Vec<_>>()
+		.try_into()
+		.map_err(|_| invalid())
+}
+
+pub(crate) fn invalid(cursor: &str) -> Error {
+	Error::Input {
+		message: format!("`{cursor}` is not a valid cursor."),
+		expected: String::from("the `next_cursor` of a previous response"),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{decode, encode};
+
+	#[test]
+	fn round_trip() {
+		let cursor = encode(&["2023-03-12 18:16:15", "1337"]);
+		assert_eq!(decode::<2>(&cursor).unwrap(), ["2023-03-12 18:16:15", "1337"]);
+
+		let cursor = encode(&["kz_ä_漢字"]);
+		assert_eq!(decode::<1>(&cursor).unwrap(), ["kz_ä_漢字"]);
+	}
+
+	#[test]
+	fn cursors_are_opaque() {
+		let cursor = encode(&["kz_lionharder", "42"]);
+		assert!(cursor
+			.chars()
+			.all(|char| char.is_ascii_hexdigit()));
+	}
+
+	#[test]
+	fn wrong_amount_of_keys() {
+		let cursor = encode(&["a", "b"]);
+		assert!(decode::<1>(&cursor).is_err());
+		assert!(decode::<3>(&cursor).is_err());
+	}
+
+	#[test]
+	fn garbage_is_rejected() {
+		// odd length
+		assert!(decode::<1>("abc").is_err());
+		// not hex
+		assert!(decode::<1>("zz").is_err());
+		// multibyte chars must not cause a panic when slicing
+		assert!(decode::<1>("ää").is_err());
+		// valid hex, invalid UTF-8
+		assert!(decode::<1>("ff").is_err());
+	}
+
+	#[test]
+	fn invalid_cursors_are_input_errors() {
+		let error = decode::<1>("zz").unwrap_err();
+		assert_eq!(error.code(), "invalid_input");
+	}
+}
@@ -1,20 +1,22 @@
 use {
 	axum::{
-		error_handling::HandleErrorLayer,
-		http::StatusCode,
+		middleware,
 		routing::{get, post},
-		BoxError, Router,
+		Extension, Router,
 	},
 	clap::Parser,
 	color_eyre::Result as Eyre,
 	log::{debug, info},
 	serde::{Deserialize, Serialize},
 	sqlx::{mysql::MySqlPoolOptions, MySql, Pool},
-	std::{net::SocketAddr, path::PathBuf, time::Duration},
-	tower::{buffer::BufferLayer, limit::RateLimitLayer, ServiceBuilder},
+	std::{net::SocketAddr, path::PathBuf},
 };
 
+mod auth;
+mod cursor;
+mod openapi;
 mod ser_date;
+mod v2;
 
 mod models;
 pub(crate) use models::{error::Error, Response, ResponseBody};
@@ -51,52 +53,75 @@ async fn main() -> Eyre<()> {
 		.await?;
 	debug!("Connected to database.");
 
+	let live_feed = routes::records::live_feed(pool.clone()).await?;
+
+	let auth = auth::Auth::new(
+		pool.clone(),
+		config
+			.anonymous_requests_per_minute
+			.unwrap_or(60),
+	);
+
 	let global_state = GlobalState { pool };
 
-	/* TODO:
-	 * `/records/top/world_records`
-	 */
+	// v2 serves the same routes, `v2::envelope` takes care of the different response format
+	let api = Router::new()
+		.route("/", get(routes::index))
+		.route("/openapi.json", get(routes::openapi))
+		.route("/modes/:ident", get(routes::modes::ident))
+		.route("/modes/", get(routes::modes::index))
+		.route("/modes", get(routes::modes::index))
+		.route("/players/:ident", get(routes::players::ident))
+		.route("/players/", get(routes::players::index))
+		.route("/players", get(routes::players::index))
+		.route("/players/:ident/completion", get(routes::players::completion))
+		.route("/players/:ident/points", get(routes::players::points))
+		.route("/players/ranking", get(routes::players::ranking))
+		.route("/players/compare", get(routes::players::compare))
+		.route("/players/search", get(routes::players::search))
+		.route("/players/:ident/progression", get(routes::players::progression))
+		.route("/players/:ident/unfinished", get(routes::players::unfinished))
+		.route("/players/:ident/names", get(routes::players::names))
+		.route("/players/:ident/bans", get(routes::players::bans))
+		.route("/servers/:ident", get(routes::servers::ident))
+		.route("/servers/:ident/stats", get(routes::servers::stats))
+		.route("/servers/", get(routes::servers::index))
+		.route("/servers", get(routes::servers::index))
+		.route("/maps/:ident", get(routes::maps::ident))
+		.route("/maps/", get(routes::maps::index))
+		.route("/maps", get(routes::maps::index))
+		.route("/maps/filters", get(routes::maps::filters))
+		.route("/maps/:ident/stats", get(routes::maps::stats))
+		.route("/mappers/:ident", get(routes::mappers::ident))
+		.route("/bans", get(routes::bans::index))
+		.route("/records/:id", get(routes::records::id))
+		.route("/records/", get(routes::records::index))
+		.route("/records", get(routes::records::index))
+		// TODO: when filtering by mode, exclude courses that aren't possible
+		.route("/records/top/player/:ident", get(routes::records::player))
+		.route("/records/top/map/:ident", get(routes::records::map))
+		.route("/records/top/map/:ident/around/:player", get(routes::records::around))
+		.route("/records/top/world_records", get(routes::records::world_records))
+		.route("/records/top/wr_leaderboard", get(routes::records::wr_leaderboard))
+		.route("/records/place/:id", get(routes::records::place))
+		.route("/records/place", get(routes::records::hypothetical_place))
+		.route("/records/live", get(routes::records::live))
+		.route("/twitch_info", post(routes::twitch_info::update))
+		.route("/twitch_info", get(routes::twitch_info::index))
+		.route("/twitch_info/:channel", get(routes::twitch_info::ident));
+
 	let router = Router::new()
 		.route("/", get(routes::index))
 		.route("/api/", get(routes::index))
-		.route("/api", get(routes::index))
-		.route("/api/modes/:ident", get(routes::modes::ident))
-		.route("/api/modes/", get(routes::modes::index))
-		.route("/api/modes", get(routes::modes::index))
-		.route("/api/players/:ident", get(routes::players::ident))
-		.route("/api/players/", get(routes::players::index))
-		.route("/api/players", get(routes::players::index))
-		.route("/api/players/:ident/completion", get(routes::players::completion))
-		.route("/api/servers/:ident", get(routes::servers::ident))
-		.route("/api/servers/", get(routes::servers::index))
-		.route("/api/servers", get(routes::servers::index))
-		.route("/api/maps/:ident", get(routes::maps::ident))
-		.route("/api/maps/", get(routes::maps::index))
-		.route("/api/maps", get(routes::maps::index))
-		.route("/api/maps/filters", get(routes::maps::filters))
-		.route("/api/records/:id", get(routes::records::id))
-		.route("/api/records/", get(routes::records::index))
-		.route("/api/records", get(routes::records::index))
-		// TODO: when filtering by mode, exclude courses that aren't possible
-		.route("/api/records/top/player/:ident", get(routes::records::player))
-		.route("/api/records/top/map/:ident", get(routes::records::map))
-		// TODO: parameters?
-		.route("/api/records/place/:id", get(routes::records::place))
-		.route("/api/twitch_info", post(routes::twitch_info).layer(
-			ServiceBuilder::new().layer(HandleErrorLayer::new(|why: BoxError| async move {
-				(
-					StatusCode::TOO_MANY_REQUESTS,
-					why.to_string()
-				)
-			}))
-			.layer(BufferLayer::new(1024))
-			.layer(RateLimitLayer::new(5, Duration::from_secs(10)))
-		))
-		// .route("/api/twitch_info", post(routes::twitch_info))
+		.nest("/api", api.clone())
+		.nest(v2::PREFIX, api)
+		.layer(Extension(live_feed))
+		.layer(middleware::from_fn_with_state(auth, auth::middleware))
+		.layer(middleware::from_fn(v2::envelope))
 		.with_state(global_state);
 
 	axum::Server::bind(&addr)
-		.serve(router.into_make_service())
+		.serve(router.into_make_service_with_connect_info::<SocketAddr>())
 		.await
 		.expect("Failed to run server.");
 
@@ -127,6 +152,8 @@ struct Config {
 	port: u16,
 	mysql_url: String,
 	log_level: Option<String>,
+	/// Requests per minute for callers without an API key. Defaults to 60.
+	anonymous_requests_per_minute: Option<u32>,
 }
 
 #[derive(Clone)]
@@ -19,6 +19,7 @@ pub(crate) enum Error {
 	Infallible,
 	Custom { message: String },
 	Database { message: String },
+	NotFound { message: String },
 	GOKZ { message: String },
 	Input { message: String, expected: String },
 	JSON,
@@ -31,9 +32,10 @@ impl Display for Error {
 		f.write_str(&match self {
 			Self::Unknown => String::from("Unknown error occurred."),
 			Self::Infallible => String::from("Encountered error which should not have happened."),
-			Self::Custom { message } | Error::Database { message } | Error::GOKZ { message } => {
-				message.to_owned()
-			}
+			Self::Custom { message }
+			| Error::Database { message }
+			| Error::NotFound { message }
+			| Error::GOKZ { message } => message.to_owned(),
 			Self::Input { message, expected } => format!("{message} Expected `{expected}`."),
 			Self::JSON => String::from("Failed to parse JSON."),
 			Self::Date => String::from("Invalid Date format."),
@@ -42,14 +44,58 @@ impl Display for Error {
 	}
 }
 
+impl Error {
+	/// Machine-readable name of the error, used by `/api/v2`.
+	pub(crate) fn code(&self) -> &'static str {
+		match self {
+			Self::Unknown | Self::Infallible | Self::Custom { .. } => "internal_error",
+			Self::Database { .. } => "database_error",
+			Self::NotFound { .. } => "not_found",
+			Self::GOKZ { .. } => "invalid_identifier",
+			Self::Input { .. } => "invalid_input",
+			Self::JSON => "invalid_json",
+			Self::Date => "invalid_date",
+			Self::DateRange => "invalid_date_range",
+		}
+	}
+
+	/// The status code used by `/api/v2`. v1 keeps its old (sometimes odd) status codes.
+	pub(crate) fn status(&self) -> StatusCode {
+		match self {
+			Self::NotFound { .. } => StatusCode::NOT_FOUND,
+			Self::GOKZ { .. } | Self::Input { .. } | Self::Date | Self::DateRange => {
+				StatusCode::UNPROCESSABLE_ENTITY
+			}
+			Self::Unknown
+			| Self::Infallible
+			| Self::Custom { .. }
+			| Self::Database { .. }
+			| Self::JSON => StatusCode::INTERNAL_SERVER_ERROR,
+		}
+	}
+
+	pub(crate) fn expected(&self) -> Option<&str> {
+		match self {
+			Self::Input { expected, .. } => Some(expected),
+			_ => None,
+		}
+	}
+}
+
 impl IntoResponse for Error {
 	fn into_response(self) -> Response {
-		match self {
+		let mut response = match &self {
 			Self::Input { .. } => (StatusCode::BAD_REQUEST, Json(self.to_string())),
-			Self::Database { message } => (StatusCode::NO_CONTENT, Json(message)),
+			Self::Database { message } | Self::NotFound { message } => {
+				(StatusCode::NO_CONTENT, Json(message.to_owned()))
+			}
 			_ => (StatusCode::INTERNAL_SERVER_ERROR, Json(self.to_string())),
 		}
-		.into_response()
+		.into_response();
+
+		// `/api/v2` builds its own error response from this
+		response.extensions_mut().insert(self);
+		response
 	}
 }
 
@@ -57,7 +103,7 @@ impl From<sqlx::Error> for Error {
 	fn from(value: sqlx::Error) -> Self {
 		warn!("SQL Error: {value:?}");
 		if let SQLError::RowNotFound = value {
-			Self::Database {
+			Self::NotFound {
 				message: String::from("No entries found."),
 			}
 		} else {
@@ -83,8 +129,12 @@ impl From<std::convert::Infallible> for Error {
 
 impl From<color_eyre::Report> for Error {
 	fn from(value: color_eyre::Report) -> Self {
-		Self::Custom {
-			message: value.to_string(),
+		// the `database` crate wraps SQL errors, e.g. when looking up an unknown player
+		match value.downcast::<SQLError>() {
+			Ok(sql_error) => sql_error.into(),
+			Err(value) => Self::Custom {
+				message: value.to_string(),
+			},
 		}
 	}
 }
@@ -94,3 +144,33 @@ impl From<ParseError> for Error {
 		Self::Date
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::*,
+		color_eyre::{eyre::eyre, Result as Eyre},
+	};
+
+	/// Same as the lookups in `database::crd::read` when nothing matches.
+	fn lookup() -> Eyre<()> {
+		Err(SQLError::RowNotFound)?
+	}
+
+	#[test]
+	fn unknown_identifier_is_not_found() {
+		let error = Error::from(lookup().unwrap_err());
+
+		assert_eq!(error.code(), "not_found");
+		assert_eq!(error.status(), StatusCode::NOT_FOUND);
+	}
+
+	#[test]
+	fn other_reports_are_internal() {
+		let error = Error::from(eyre!("something broke"));
+
+		assert_eq!(error.code(), "internal_error");
+		assert_eq!(error.status(), StatusCode::INTERNAL_SERVER_ERROR);
+		assert_eq!(error.to_string(), "something broke");
+	}
+}
@@ -11,4 +11,6 @@ pub(crate) type Response<T> = Result<Json<ResponseBody<T>>, Error>;
 pub(crate) struct ResponseBody<T> {
 	pub(crate) result: T,
 	pub(crate) took: u128,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) next_cursor: Option<String>,
 }
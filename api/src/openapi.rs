@@ -0,0 +1,435 @@
+//! A small OpenAPI 3 generator.
+//!
+//! Every type that shows up in a request or response implements [`Schema`], usually through the
+//! [`schema!`] macro. Route modules register their endpoints with a [`Spec`] in their `docs`
+//! function; the resulting document is served at `/api/openapi.json` and also used to render the
+//! index page.
+
+use {
+	database::schemas::{FancyPlayer, PlayerRow},
+	gokz_rs::prelude::SteamID,
+	serde_json::{json, Map, Value},
+	sqlx::types::time::PrimitiveDateTime,
+	std::sync::OnceLock,
+};
+
+pub(crate) const MODE_IDENTIFIER: &str = "Name (e.g. `kz_simple`) or ID (e.g. `201`)";
+pub(crate) const PLAYER_IDENTIFIER: &str =
+	"Name (e.g. `AlphaKeks`, slow), SteamID (e.g. `STEAM_1:1:161178172`) or SteamID64 (e.g. `76561198282622073`)";
+pub(crate) const MAP_IDENTIFIER: &str = "Name (e.g. `lionharder`) or ID (e.g. `992`)";
+pub(crate) const SERVER_IDENTIFIER: &str = "Name (e.g. `Hikari KZ`) or ID (e.g. `999`)";
+pub(crate) const INCLUDE_BANNED: &str = "Include records of banned players. Defaults to `false`.";
+
+pub(crate) trait Schema {
+	/// Whether the value may be omitted (or `null`).
+	const OPTIONAL: bool = false;
+
+	fn schema(spec: &mut Spec) -> Value;
+
+	/// The fields of an object. Used to turn `Params` structs into query parameters.
+	fn fields(_spec: &mut Spec) -> Vec<Field> {
+		Vec::new()
+	}
+}
+
+#[derive(Debug)]
+pub(crate) struct Field {
+	pub(crate) name: &'static str,
+	pub(crate) schema: Value,
+	pub(crate) required: bool,
+}
+
+/// Implements [`Schema`] for a struct. Every field has to be listed, otherwise this fails to
+/// compile, so the spec can't silently drift away from the code. Fields marked with
+/// `#[flatten]` are merged into the parent and fields marked with `#[skip]` are left out, just
+/// like the serde attributes of the same name.
+macro_rules! schema {
+	($name:ident { $($(#[$marker:ident])? $field:ident),* $(,)? }) => {
+		impl $crate::openapi::Schema for $name {
+			fn schema(spec: &mut $crate::openapi::Spec) -> ::serde_json::Value {
+				spec.component::<Self>(stringify!($name))
+			}
+
+			fn fields(spec: &mut $crate::openapi::Spec) -> Vec<$crate::openapi::Field> {
+				#[allow(dead_code)]
+				fn exhaustive($name { $($field: _),* }: &$name) {}
+
+				[$($crate::openapi::schema!(@field spec, $name, $field $(, $marker)?)),*]
+					.into_iter()
+					.flatten()
+					.collect()
+			}
+		}
+	};
+	(@field $spec:ident, $name:ident, $field:ident) => {
+		vec![$crate::openapi::field(stringify!($field), $spec, |value: &$name| &value.$field)]
+	};
+	(@field $spec:ident, $name:ident, $field:ident, flatten) => {
+		$crate::openapi::flatten($spec, |value: &$name| &value.$field)
+	};
+	(@field $spec:ident, $name:ident, $field:ident, skip) => {
+		Vec::new()
+	};
+}
+
+pub(crate) use schema;
+
+/// Describes a single struct field. The accessor is never called, it only tells the compiler
+/// which type the field has.
+pub(crate) fn field<S, T: Schema>(
+	name: &'static str,
+	spec: &mut Spec,
+	_accessor: impl Fn(&S) -> &T,
+) -> Field {
+	Field {
+		name,
+		schema: T::schema(spec),
+		required: !T::OPTIONAL,
+	}
+}
+
+/// Like [`field`], but for `#[serde(flatten)]` fields.
+pub(crate) fn flatten<S, T: Schema>(spec: &mut Spec, _accessor: impl Fn(&S) -> &T) -> Vec<Field> {
+	T::fields(spec)
+}
+
+macro_rules! primitive {
+	($($ty:ty => $schema:tt),* $(,)?) => {
+		$(impl Schema for $ty {
+			fn schema(_: &mut Spec) -> Value {
+				json!($schema)
+			}
+		})*
+	};
+}
+
+primitive! {
+	bool => { "type": "boolean" },
+	u8 => { "type": "integer", "minimum": 0, "maximum": 255 },
+	u16 => { "type": "integer", "minimum": 0, "maximum": 65535 },
+	u32 => { "type": "integer", "minimum": 0 },
+	u64 => { "type": "integer", "minimum": 0 },
+	u128 => { "type": "integer", "minimum": 0 },
+	usize => { "type": "integer", "minimum": 0 },
+	i32 => { "type": "integer" },
+	i64 => { "type": "integer" },
+	f64 => { "type": "number" },
+	String => { "type": "string" },
+	SteamID => { "type": "string", "example": "STEAM_1:1:161178172" },
+	PrimitiveDateTime => { "type": "string", "example": "2023-01-01T13:37:00" },
+}
+
+impl Schema for () {
+	fn schema(_: &mut Spec) -> Value {
+		json!({ "type": "object" })
+	}
+}
+
+impl<T: Schema> Schema for Option<T> {
+	const OPTIONAL: bool = true;
+
+	fn schema(spec: &mut Spec) -> Value {
+		let mut schema = T::schema(spec);
+		match schema.as_object_mut() {
+			// `$ref` doesn't allow any siblings
+			Some(object) if object.contains_key("$ref") => {
+				json!({ "allOf": [schema], "nullable": true })
+			}
+			Some(object) => {
+				object.insert(String::from("nullable"), Value::Bool(true));
+				schema
+			}
+			None => schema,
+		}
+	}
+}
+
+impl<T: Schema> Schema for Vec<T> {
+	fn schema(spec: &mut Spec) -> Value {
+		json!({ "type": "array", "items": T::schema(spec) })
+	}
+}
+
+schema!(FancyPlayer {
+	id,
+	name,
+	steam_id,
+	steam_id64,
+	is_banned
+});
+schema!(PlayerRow {
+	id,
+	name,
+	is_banned
+});
+
+#[derive(Debug, Default)]
+pub(crate) struct Spec {
+	paths: Map<String, Value>,
+	components: Map<String, Value>,
+}
+
+/// A single registered endpoint. Returned by [`Spec::get`] and friends to refine path
+/// parameters.
+pub(crate) struct Operation<'spec> {
+	spec: &'spec mut Spec,
+	path: String,
+	method: &'static str,
+}
+
+impl Spec {
+	/// Registers a `GET` endpoint which responds with the usual JSON envelope around `R` and
+	/// takes its query parameters from `P`.
+	pub(crate) fn get<P: Schema, R: Schema>(&mut self, path: &str, summary: &str) -> Operation<'_> {
+		let result = R::schema(self);
+		let response = json!({
+			"description": "OK",
+			"content": {
+				"application/json": {
+					"schema": {
+						"type": "object",
+						"required": ["result", "took"],
+						"properties": {
+							"result": result,
+							"took": {
+								"type": "integer",
+								"description": "How long the request took to process, in nanoseconds."
+							},
+							"next_cursor": {
+								"type": "string",
+								"description": "Pass this as `cursor` to get the next page."
+							}
+						}
+					}
+				}
+			}
+		});
+
+		self.operation::<P>("get", path, summary, response, None)
+	}
+
+	/// Registers a `GET` endpoint which streams `R`s as server-sent events.
+	pub(crate) fn stream<P: Schema, R: Schema>(
+		&mut self,
+		path: &str,
+		summary: &str,
+	) -> Operation<'_> {
+		let event = R::schema(self);
+		let response = json!({
+			"description": "A stream of server-sent events, one JSON object per event.",
+			"content": { "text/event-stream": { "schema": event } }
+		});
+
+		self.operation::<P>("get", path, summary, response, None)
+	}
+
+	/// Registers a `POST` endpoint which takes a JSON body of type `B` and only responds with a
+	/// status code.
+	pub(crate) fn post<B: Schema>(&mut self, path: &str, summary: &str) -> Operation<'_> {
+		let body = json!({
+			"required": true,
+			"content": { "application/json": { "schema": B::schema(self) } }
+		});
+		let response = json!({ "description": "OK" });
+
+		self.operation::<()>("post", path, summary, response, Some(body))
+	}
+
+	fn operation<P: Schema>(
+		&mut self,
+		method: &'static str,
+		path: &str,
+		summary: &str,
+		response: Value,
+		body: Option<Value>,
+	) -> Operation<'_> {
+		let mut parameters = path
+			.split('/')
+			.filter_map(|segment| {
+				segment
+					.strip_prefix('{')?
+					.strip_suffix('}')
+			})
+			.map(|name| {
+				json!({
+					"name": name,
+					"in": "path",
+					"required": true,
+					"schema": { "type": "string" }
+				})
+			})
+			.collect::<Vec<_>>();
+
+		parameters.extend(
+			P::fields(self)
+				.into_iter()
+				.map(|mut field| {
+					// missing query parameters are expressed through `required`, not `null`
+					if let Some(schema) = field.schema.as_object_mut() {
+						schema.remove("nullable");
+					}
+
+					json!({
+						"name": field.name,
+						"in": "query",
+						"required": field.required,
+						"schema": field.schema
+					})
+				}),
+		);
+
+		let tag = path
+			.trim_start_matches("/api")
+			.split('/')
+			.find(|segment| !segment.is_empty())
+			.unwrap_or("index");
+
+		let mut operation = json!({
+			"summary": summary,
+			"tags": [tag],
+			"parameters": parameters,
+			"responses": {
+				"200": response,
+				"204": { "$ref": "#/components/responses/NotFound" },
+				"400": { "$ref": "#/components/responses/BadRequest" },
+				"401": { "$ref": "#/components/responses/Unauthorized" },
+				"403": { "$ref": "#/components/responses/Forbidden" },
+				"429": { "$ref": "#/components/responses/TooManyRequests" },
+				"500": { "$ref": "#/components/responses/InternalServerError" }
+			}
+		});
+
+		if let Some(body) = body {
+			operation["requestBody"] = body;
+		}
+
+		self.paths
+			.entry(path)
+			.or_insert_with(|| json!({}))[method] = operation;
+
+		Operation {
+			spec: self,
+			path: path.to_owned(),
+			method,
+		}
+	}
+
+	/// Adds `T` to `components.schemas` (if it isn't already there) and returns a reference to
+	/// it.
+	pub(crate) fn component<T: Schema>(&mut self, name: &str) -> Value {
+		if !self.components.contains_key(name) {
+			// insert a placeholder first so recursive types terminate
+			self.components
+				.insert(name.to_owned(), Value::Null);
+
+			let fields = T::fields(self);
+			let required = fields
+				.iter()
+				.filter(|field| field.required)
+				.map(|field| field.name)
+				.collect::<Vec<_>>();
+			let properties = fields
+				.into_iter()
+				.map(|field| (field.name.to_owned(), field.schema))
+				.collect::<Map<_, _>>();
+
+			self.components.insert(
+				name.to_owned(),
+				json!({ "type": "object", "required": required, "properties": properties }),
+			);
+		}
+
+		json!({ "$ref": format!("#/components/schemas/{name}") })
+	}
+
+	fn finish(self) -> Value {
+		let error = |description: &str| {
+			json!({
+				"description": description,
+				"content": { "application/json": { "schema": { "type": "string" } } }
+			})
+		};
+
+		json!({
+			"openapi": "3.0.3",
+			"info": {
+				"title": "SchnoseAPI",
+				"version": env!("CARGO_PKG_VERSION")
+			},
+			"paths": self.paths,
+			"components": {
+				"schemas": self.components,
+				"responses": {
+					"NotFound": error("No entries found."),
+					"BadRequest": error("Invalid input."),
+					"Unauthorized": error("Invalid API key."),
+					"Forbidden": error("The API key is missing a required scope."),
+					"TooManyRequests": {
+						"description": "Rate limit exceeded. Try again after `Retry-After` seconds.",
+						"headers": {
+							"Retry-After": { "schema": { "type": "integer" } }
+						},
+						"content": { "application/json": { "schema": { "type": "string" } } }
+					},
+					"InternalServerError": error("Something went wrong on our end.")
+				},
+				"securitySchemes": {
+					"api_key": {
+						"type": "apiKey",
+						"in": "header",
+						"name": crate::auth::API_KEY_HEADER
+					}
+				}
+			},
+			// API keys are optional, they only raise the rate limit
+			"security": [{}, { "api_key": [] }]
+		})
+	}
+}
+
+impl Operation<'_> {
+	/// Sets the type and description of a path parameter.
+	pub(crate) fn path_param<T: Schema>(self, name: &str, description: &str) -> Self {
+		let schema = T::schema(self.spec);
+		let parameters = self.spec.paths[&self.path][self.method]["parameters"]
+			.as_array_mut()
+			.expect("parameters are always an array");
+
+		if let Some(parameter) = parameters
+			.iter_mut()
+			.find(|parameter| parameter["in"] == "path" && parameter["name"] == name)
+		{
+			parameter["schema"] = schema;
+			parameter["description"] = Value::from(description);
+		}
+
+		self
+	}
+
+	/// Sets the description of a query parameter.
+	pub(crate) fn query_param(self, name: &str, description: &str) -> Self {
+		let parameters = self.spec.paths[&self.path][self.method]["parameters"]
+			.as_array_mut()
+			.expect("parameters are always an array");
+
+		if let Some(parameter) = parameters
+			.iter_mut()
+			.find(|parameter| parameter["in"] == "query" && parameter["name"] == name)
+		{
+			parameter["description"] = Value::from(description);
+		}
+
+		self
+	}
+}
+
+/// The OpenAPI document for every route. Built once on first use.
+pub(crate) fn spec() -> &'static Value {
+	static SPEC: OnceLock<Value> = OnceLock::new();
+
+	SPEC.get_or_init(|| {
+		let mut spec = Spec::default();
+		crate::routes::docs(&mut spec);
+		spec.finish()
+	})
+}
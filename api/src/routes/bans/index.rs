@@ -0,0 +1,112 @@
+use {
+	super::{Ban, BanQuery, BAN_QUERY},
+	crate::{openapi::schema, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Query, State},
+		Json,
+	},
+	chrono::NaiveDateTime,
+	database::crd::read::get_player,
+	gokz_rs::prelude::*,
+	log::debug,
+	serde::Deserialize,
+	sqlx::QueryBuilder,
+	std::time::Instant,
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	since: Option<String>,
+	player: Option<String>,
+	ban_type: Option<String>,
+	active: Option<bool>,
+	limit: Option<u32>,
+}
+
+schema!(Params {
+	since,
+	player,
+	ban_type,
+	active,
+	limit
+});
+
+pub(crate) async fn get(
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Vec<Ban>> {
+	let start = Instant::now();
+	debug!("[bans::index::get]");
+	debug!("> `params`: {params:#?}");
+
+	let mut query = QueryBuilder::new(BAN_QUERY);
+	let mut multiple_filters = false;
+
+	if let Some(since) = params.since {
+		let since = NaiveDateTime::parse_from_str(&since, "%Y-%m-%dT%H:%M:%S")?
+			.format("%Y-%m-%d %H:%M:%S")
+			.to_string();
+
+		query
+			.push(" WHERE b.created_on >= ")
+			.push_bind(since);
+
+		multiple_filters = true;
+	}
+
+	if let Some(player_ident) = params.player {
+		let player_ident = player_ident.parse::<PlayerIdentifier>()?;
+		let player_id = get_player(player_ident, &pool)
+			.await
+			.map(|player_row| player_row.id)?;
+
+		query
+			.push(if multiple_filters { " AND " } else { " WHERE " })
+			.push(" b.player_id = ")
+			.push_bind(player_id);
+
+		multiple_filters = true;
+	}
+
+	if let Some(ban_type) = params.ban_type {
+		query
+			.push(if multiple_filters { " AND " } else { " WHERE " })
+			.push(" b.ban_type = ")
+			.push_bind(ban_type);
+
+		multiple_filters = true;
+	}
+
+	if let Some(active) = params.active {
+		query
+			.push(if multiple_filters { " AND " } else { " WHERE " })
+			.push(if active { "" } else { " NOT " })
+			.push(" (b.expires_on IS NULL OR b.expires_on > CURRENT_TIMESTAMP) ");
+	}
+
+	let limit = params
+		.limit
+		.map_or(100, |limit| limit.min(1000));
+
+	query
+		.push(" ORDER BY b.created_on DESC, b.id DESC LIMIT ")
+		.push_bind(limit);
+
+	let result = query
+		.build_query_as::<BanQuery>()
+		.fetch_all(&pool)
+		.await?
+		.into_iter()
+		.map(Ban::from)
+		.collect::<Vec<_>>();
+
+	if result.is_empty() {
+		return Err(sqlx::Error::RowNotFound.into());
+	}
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
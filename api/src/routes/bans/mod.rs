@@ -0,0 +1,105 @@
+use {
+	crate::{
+		openapi::{schema, Spec, PLAYER_IDENTIFIER},
+		ser_date::{ser_date, ser_opt_date},
+	},
+	database::schemas::{account_id_to_steam_id64, FancyPlayer},
+	gokz_rs::prelude::SteamID,
+	serde::Serialize,
+	sqlx::{types::time::PrimitiveDateTime, FromRow},
+};
+
+mod index;
+pub(crate) use index::get as index;
+
+#[derive(Debug, FromRow)]
+pub(crate) struct BanQuery {
+	pub(crate) id: u32,
+	pub(crate) player_id: u32,
+	pub(crate) player_name: String,
+	pub(crate) player_is_banned: bool,
+	pub(crate) ban_type: String,
+	pub(crate) reason: String,
+	pub(crate) server_id: u16,
+	pub(crate) created_on: PrimitiveDateTime,
+	pub(crate) expires_on: Option<PrimitiveDateTime>,
+	pub(crate) updated_on: PrimitiveDateTime,
+	pub(crate) active: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Ban {
+	pub(crate) id: u32,
+	pub(crate) player: FancyPlayer,
+	pub(crate) ban_type: String,
+	pub(crate) reason: String,
+	pub(crate) server_id: u16,
+	#[serde(serialize_with = "ser_date")]
+	pub(crate) created_on: PrimitiveDateTime,
+	/// `null` for permanent bans.
+	#[serde(serialize_with = "ser_opt_date")]
+	pub(crate) expires_on: Option<PrimitiveDateTime>,
+	#[serde(serialize_with = "ser_date")]
+	pub(crate) updated_on: PrimitiveDateTime,
+	pub(crate) active: bool,
+}
+
+schema!(Ban {
+	id,
+	player,
+	ban_type,
+	reason,
+	server_id,
+	created_on,
+	expires_on,
+	updated_on,
+	active
+});
+
+pub(crate) const BAN_QUERY: &str = r#"
+	SELECT
+	  b.id AS id,
+	  p.id AS player_id,
+	  p.name AS player_name,
+	  p.is_banned AS player_is_banned,
+	  b.ban_type AS ban_type,
+	  b.reason AS reason,
+	  b.server_id AS server_id,
+	  b.created_on AS created_on,
+	  b.expires_on AS expires_on,
+	  b.updated_on AS updated_on,
+	  (b.expires_on IS NULL OR b.expires_on > CURRENT_TIMESTAMP) AS active
+	FROM bans AS b
+	JOIN players AS p ON p.id = b.player_id
+"#;
+
+impl From<BanQuery> for Ban {
+	fn from(ban: BanQuery) -> Self {
+		let steam_id64 = account_id_to_steam_id64(ban.player_id);
+		let steam_id = SteamID::from(steam_id64);
+		Self {
+			id: ban.id,
+			player: FancyPlayer {
+				id: ban.player_id,
+				name: ban.player_name,
+				steam_id: steam_id.to_string(),
+				steam_id64: steam_id64.to_string(),
+				is_banned: ban.player_is_banned,
+			},
+			ban_type: ban.ban_type,
+			reason: ban.reason,
+			server_id: ban.server_id,
+			created_on: ban.created_on,
+			expires_on: ban.expires_on,
+			updated_on: ban.updated_on,
+			active: ban.active,
+		}
+	}
+}
+
+pub(crate) fn docs(spec: &mut Spec) {
+	spec.get::<index::Params, Vec<Ban>>("/api/bans", "Get bans, most recent first.")
+		.query_param("since", "Only bans created after this date (e.g. `2023-01-01T00:00:00`).")
+		.query_param("player", PLAYER_IDENTIFIER)
+		.query_param("active", "Only bans which haven't expired yet (or only expired ones).");
+}
@@ -1,9 +1,153 @@
-use axum::response::Html;
+use {
+	crate::{auth::API_KEY_HEADER, openapi},
+	axum::response::Html,
+	serde_json::Value,
+	std::{fmt::Write, sync::OnceLock},
+};
 
-#[rustfmt::skip]
 pub async fn get() -> Html<&'static str> {
-	Html(r#"
-<!DOCTYPE html>
+	static PAGE: OnceLock<String> = OnceLock::new();
+	Html(PAGE.get_or_init(render))
+}
+
+/// Renders the docs page from the OpenAPI spec, so it can't get out of sync with the routes.
+fn render() -> String {
+	let spec = openapi::spec();
+	let mut html = String::from(HEADER);
+
+	writeln!(
+		html,
+		r#"	<h4>A machine-readable version of this page is available at <a href="/api/openapi.json"><code>/api/openapi.json</code></a>.</h4>
+
+	<h4>Every endpoint is also available under <code>/api/v2</code>. v2 wraps results as <code>{{ data, meta, request_id }}</code>, where <code>meta</code> contains <code>took_ms</code>, <code>count</code>, <code>limit</code>, <code>next_cursor</code> and <code>has_more</code>. Errors look like <code>{{ error: {{ code, message, expected, request_id }} }}</code> and use proper status codes (<code>404</code> if nothing was found, <code>422</code> for invalid identifiers and parameters). The request ID is also sent as the <code>x-request-id</code> header.</h4>
+
+	<h4>List endpoints which accept a <code>cursor</code> return a <code>next_cursor</code> if there are more results. Pass it as <code>cursor</code> to get the next page.</h4>
+
+	<h4>Anonymous requests are limited per IP address. Send an API key in the <code>{API_KEY_HEADER}</code> header to get the key's own quota. Every response carries <code>x-ratelimit-limit</code> and <code>x-ratelimit-remaining</code> headers; once the quota for the current minute is used up, requests fail with <code>429</code> and a <code>Retry-After</code> header. Unknown or revoked keys get <code>401</code>, keys without the required scope (<code>read</code> for all <code>GET</code> endpoints) get <code>403</code>.</h4>"#
+	)
+	.unwrap();
+
+	let paths = spec["paths"]
+		.as_object()
+		.into_iter()
+		.flatten();
+
+	for (path, operations) in paths {
+		let operations = operations
+			.as_object()
+			.into_iter()
+			.flatten();
+
+		for (method, operation) in operations {
+			writeln!(
+				html,
+				"\n\t<h3><code>{} {}</code></h3>\n\t<h4>{}</h4>",
+				method.to_uppercase(),
+				escape(path),
+				inline_code(
+					operation["summary"]
+						.as_str()
+						.unwrap_or_default()
+				),
+			)
+			.unwrap();
+
+			let parameters = operation["parameters"]
+				.as_array()
+				.into_iter()
+				.flatten()
+				.collect::<Vec<_>>();
+
+			if !parameters.is_empty() {
+				html.push_str("\t<ul>\n");
+				for parameter in parameters {
+					write!(
+						html,
+						"\t\t<li><code>{}</code>: <code>{}</code>",
+						escape(
+							parameter["name"]
+								.as_str()
+								.unwrap_or_default()
+						),
+						type_name(&parameter["schema"], parameter["required"] == true),
+					)
+					.unwrap();
+
+					if let Some(description) = parameter["description"].as_str() {
+						write!(html, " ({})", inline_code(description)).unwrap();
+					}
+
+					html.push_str("</li>\n");
+				}
+				html.push_str("\t</ul>\n");
+			}
+
+			let response = &operation["responses"]["200"]["content"];
+			let returns = match (
+				response["application/json"]["schema"]["properties"]["result"].is_null(),
+				response["text/event-stream"]["schema"].is_null(),
+			) {
+				(false, _) => Some(&response["application/json"]["schema"]["properties"]["result"]),
+				(true, false) => Some(&response["text/event-stream"]["schema"]),
+				(true, true) => None,
+			};
+
+			if let Some(returns) = returns {
+				writeln!(html, "\t<h4>Returns <code>{}</code></h4>", type_name(returns, true))
+					.unwrap();
+			}
+		}
+	}
+
+	html.push_str(FOOTER);
+	html
+}
+
+/// A short, Rust-like name for a schema, e.g. `Vec<Record>` or `Option<integer>`.
+fn type_name(schema: &Value, required: bool) -> String {
+	let name = if let Some(reference) = schema["$ref"].as_str() {
+		escape(
+			reference
+				.rsplit('/')
+				.next()
+				.unwrap_or(reference),
+		)
+	} else if let Some(inner) = schema["allOf"].get(0) {
+		type_name(inner, true)
+	} else if schema["type"] == "array" {
+		format!("Vec&lt;{}&gt;", type_name(&schema["items"], true))
+	} else {
+		escape(
+			schema["type"]
+				.as_str()
+				.unwrap_or("object"),
+		)
+	};
+
+	if required && schema["nullable"] != true {
+		name
+	} else {
+		format!("Option&lt;{name}&gt;")
+	}
+}
+
+fn escape(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+/// Escapes `text` and turns `backticks` into `<code>` tags.
+fn inline_code(text: &str) -> String {
+	escape(text)
+		.split('`')
+		.enumerate()
+		.map(|(i, part)| if i % 2 == 1 { format!("<code>{part}</code>") } else { part.to_owned() })
+		.collect()
+}
+
+const HEADER: &str = r#"<!DOCTYPE html>
 <html lang="en">
 <head>
 	<meta charset="UTF-8">
@@ -24,106 +168,8 @@ body {
 <body>
 	<h1>SchnoseAPI</h1>
 
-	<h3><code>/api/modes/:ident</code></h3>
-	</h4>Get 1 mode by an identifier. Identifier can be:</h4>
-	<ul>
-		<li>Name (e.g. <code>"kz_simple"</code>)</li>
-		<li>ID (e.g. <code>201</code>)</li>
-	</ul>
-
-	<h3><code>/api/modes</code></h3>
-	</h4>Get all modes.</h4>
-
-	<h3><code>/api/players/:ident</code></h3>
-	</h4>Get 1 player by an identifier. Identifier can be:</h4>
-	<ul>
-		<li>Name (e.g. <code>"AlphaKeks"</code> (THIS IS PRETTY SLOW))</li>
-		<li>SteamID (e.g. <code>"STEAM_1:1:161178172"</code>)</li>
-		<li>SteamID64 (e.g. <code>76561198282622073</code>)</li>
-	</ul>
-
-	<h3><code>/api/players</code></h3>
-	</h4>Get up to 500 players. Parameters:</h4>
-	<ul>
-		<li><code>is_banned</code>: <code>Option&lt;bool&gt;</code></li>
-		<li><code>limit</code>: <code>Option&lt;u32&gt;</code></li>
-		<li><code>offset</code>: <code>Option&lt;i32&gt;</code></li>
-	</ul>
-
-	<h3><code>/api/servers/:ident</code></h3>
-	</h4>Get a server by an identifier. Identifier can be:</h4>
-	<ul>
-		<li>Name (e.g. <code>"Hikari KZ"</code>)</li>
-		<li>ID (e.g. <code>999</code>)</li>
-	</ul>
-
-	<h3><code>/api/servers</code></h3>
-	</h4>Get servers. Parameters:</h4>
-	<ul>
-		<li><code>name</code>: <code>Option&lt;String&gt; (this can be an identifier just like above)</code></li>
-		<li><code>owned_by</code>: <code>Option&lt;String&gt; (this is a player identifier just like above)</code></li>
-		<li><code>approved_by</code>: <code>Option&lt;String&gt; (this is a player identifier just like above)</code></li>
-		<li><code>limit</code>: <code>Option&lt;u32&gt;</code></li>
-	</ul>
-
-	<h3><code>/api/maps/:ident</code></h3>
-	</h4>Get a map by an identifier. Identifier can be:</h4>
-	<ul>
-		<li>Name (e.g. <code>"lionharder"</code>)</li>
-		<li>ID (e.g. <code>992</code>)</li>
-	</ul>
-
-	<h3><code>/api/maps</code></h3>
-	</h4>Get maps. Parameters:</h4>
-	<ul>
-		<li><code>name</code>: <code>Option&lt;String&gt; (this can be an identifier just like above)</code></li>
-		<li><code>tier</code>: <code>Option&lt;u8&gt;</code></li>
-		<li><code>courses</code>: <code>Option&lt;u8&gt;</code></li>
-		<li><code>validated</code>: <code>Option&lt;bool&gt;</code></li>
-		<li><code>created_by</code>: <code>Option&lt;String&gt; (this is a player identifier just like above)</code></li>
-		<li><code>approved_by</code>: <code>Option&lt;String&gt; (this is a player identifier just like above)</code></li>
-		<li><code>limit</code>: <code>Option&lt;u32&gt;</code></li>
-	</ul>
-
-	<h3><code>/api/records/:id</code></h3>
-	</h4>Get a record by its ID. This matches up with the GlobalAPI.</h4>
-
-	</h4>Get records (sorted by date). Parameters:</h4>
-	<ul>
-		<li><code>mode</code>: <code>Option&lt;String&gt; (this can be an identifier just like above)</code></li>
-		<li><code>stage</code>: <code>Option&lt;u8&gt;</code></li>
-		<li><code>map</code>: <code>Option&lt;String&gt; (this can be an identifier just like above)</code></li>
-		<li><code>player</code>: <code>Option&lt;String&gt; (this can be an identifier just like above)</code></li>
-		<li><code>has_teleports</code>: <code>Option&lt;bool&gt;</code></li>
-		<li><code>created_after</code>: <code>Option&lt;String&gt; (this is a date with the following format: <code>%Y-%m-%dT%H:%M:%S</code>)</code></li>
-		<li><code>created_before</code>: <code>Option&lt;String&gt; (this is a date with the following format: <code>%Y-%m-%dT%H:%M:%S</code>)</code></li>
-		<li><code>limit</code>: <code>Option&lt;u32&gt;</code></li>
-	</ul>
-
-	<h3><code>/api/records/top/player/:ident</code></h3>
-	</h4>Get player personal bests (sorted by date). Parameters:</h4>
-	<ul>
-		<li><code>mode</code>: <code>Option&lt;String&gt; (this can be an identifier just like above)</code></li>
-		<li><code>stage</code>: <code>Option&lt;u8&gt;</code></li>
-		<li><code>map</code>: <code>Option&lt;String&gt; (this can be an identifier just like above)</code></li>
-		<li><code>has_teleports</code>: <code>Option&lt;bool&gt;</code></li>
-		<li><code>created_after</code>: <code>Option&lt;String&gt; (this is a date with the following format: <code>%Y-%m-%dT%H:%M:%S</code>)</code></li>
-		<li><code>created_before</code>: <code>Option&lt;String&gt; (this is a date with the following format: <code>%Y-%m-%dT%H:%M:%S</code>)</code></li>
-		<li><code>limit</code>: <code>Option&lt;u32&gt;</code></li>
-	</ul>
-
-	<h3><code>/api/records/top/map/:ident</code></h3>
-	</h4>Get map leaderboards. Parameters:</h4>
-	<ul>
-		<li><code>mode</code>: <code>Option&lt;String&gt; (this can be an identifier just like above)</code></li>
-		<li><code>stage</code>: <code>Option&lt;u8&gt;</code></li>
-		<li><code>player</code>: <code>Option&lt;String&gt; (this can be an identifier just like above)</code></li>
-		<li><code>has_teleports</code>: <code>Option&lt;bool&gt;</code></li>
-		<li><code>created_after</code>: <code>Option&lt;String&gt; (this is a date with the following format: <code>%Y-%m-%dT%H:%M:%S</code>)</code></li>
-		<li><code>created_before</code>: <code>Option&lt;String&gt; (this is a date with the following format: <code>%Y-%m-%dT%H:%M:%S</code>)</code></li>
-		<li><code>limit</code>: <code>Option&lt;u32&gt;</code></li>
-	</ul>
-</body>
+"#;
+
+const FOOTER: &str = r#"</body>
 </html>
-	"#)
-}
+"#;
@@ -0,0 +1,159 @@
+use {
+	crate::{openapi::schema, ser_date::ser_date, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Path, State},
+		Json,
+	},
+	database::{
+		crd::read::get_player,
+		schemas::{account_id_to_steam_id64, FancyPlayer},
+	},
+	gokz_rs::prelude::*,
+	log::debug,
+	serde::Serialize,
+	sqlx::{types::time::PrimitiveDateTime, FromRow},
+	std::time::Instant,
+};
+
+#[derive(Debug, FromRow)]
+struct MapperMapQuery {
+	id: u16,
+	name: String,
+	tier: u8,
+	validated: bool,
+	courses: i64,
+	completions: i64,
+	created_on: PrimitiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Mapper {
+	player: FancyPlayer,
+	total_maps: u32,
+	average_tier: f64,
+	total_completions: u32,
+	maps: Vec<MapperMap>,
+}
+
+schema!(Mapper {
+	player,
+	total_maps,
+	average_tier,
+	total_completions,
+	maps
+});
+
+#[derive(Debug, Serialize)]
+pub struct MapperMap {
+	id: u16,
+	name: String,
+	tier: u8,
+	validated: bool,
+	courses: u32,
+	completions: u32,
+	#[serde(serialize_with = "ser_date")]
+	created_on: PrimitiveDateTime,
+}
+
+schema!(MapperMap {
+	id,
+	name,
+	tier,
+	validated,
+	courses,
+	completions,
+	created_on
+});
+
+pub(crate) async fn get(
+	Path(player_ident): Path<String>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Mapper> {
+	let start = Instant::now();
+	debug!("[mappers::ident::get]");
+	debug!("> `player_ident`: {player_ident:#?}");
+	let player_ident = player_ident.parse::<PlayerIdentifier>()?;
+	debug!("> `player_ident`: {player_ident:#?}");
+
+	let player = get_player(player_ident, &pool).await?;
+
+	let query_result = sqlx::query_as::<_, MapperMapQuery>(
+		r#"
+		SELECT
+		  map.id AS id,
+		  map.name AS name,
+		  c.kzt_difficulty AS tier,
+		  map.validated AS validated,
+		  (
+		    SELECT COUNT(*)
+		    FROM courses AS c_count
+		    WHERE c_count.map_id = map.id
+		  ) AS courses,
+		  (
+		    SELECT COUNT(*)
+		    FROM records AS r
+		    JOIN courses AS c_records ON c_records.id = r.course_id
+		    WHERE c_records.map_id = map.id
+		  ) AS completions,
+		  map.created_on AS created_on
+		FROM maps AS map
+		JOIN courses AS c ON c.map_id = map.id AND c.stage = 0
+		WHERE map.created_by = ?
+		ORDER BY map.created_on DESC, map.id DESC
+		"#,
+	)
+	.bind(player.id)
+	.fetch_all(&pool)
+	.await?;
+
+	if query_result.is_empty() {
+		return Err(sqlx::Error::RowNotFound.into());
+	}
+
+	let maps = query_result
+		.into_iter()
+		.map(|map| MapperMap {
+			id: map.id,
+			name: map.name,
+			tier: map.tier,
+			validated: map.validated,
+			courses: map.courses as u32,
+			completions: map.completions as u32,
+			created_on: map.created_on,
+		})
+		.collect::<Vec<_>>();
+
+	let total_maps = maps.len() as u32;
+	let average_tier = maps
+		.iter()
+		.map(|map| map.tier as f64)
+		.sum::<f64>()
+		/ total_maps as f64;
+	let total_completions = maps
+		.iter()
+		.map(|map| map.completions)
+		.sum();
+
+	let steam_id64 = account_id_to_steam_id64(player.id);
+	let steam_id = SteamID::from(steam_id64);
+
+	let result = Mapper {
+		player: FancyPlayer {
+			id: player.id,
+			name: player.name,
+			steam_id: steam_id.to_string(),
+			steam_id64: steam_id64.to_string(),
+			is_banned: player.is_banned,
+		},
+		total_maps,
+		average_tier,
+		total_completions,
+		maps,
+	};
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
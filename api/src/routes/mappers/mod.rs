@@ -0,0 +1,12 @@
+use crate::openapi::{Spec, PLAYER_IDENTIFIER};
+
+mod ident;
+pub(crate) use ident::get as ident;
+
+pub(crate) fn docs(spec: &mut Spec) {
+	spec.get::<(), ident::Mapper>(
+		"/api/mappers/{ident}",
+		"Get all maps created by a player, along with some aggregated stats.",
+	)
+	.path_param::<String>("ident", PLAYER_IDENTIFIER);
+}
@@ -1,6 +1,6 @@
 use {
 	super::{Filter, FiltersRow},
-	crate::{Error, GlobalState, Response, ResponseBody},
+	crate::{openapi::schema, Error, GlobalState, Response, ResponseBody},
 	axum::{
 		extract::{Query, State},
 		Json,
@@ -24,6 +24,16 @@ pub(crate) struct Params {
 	approved_by: Option<String>,
 }
 
+schema!(Params {
+	name,
+	mode,
+	tier,
+	stage,
+	validated,
+	created_by,
+	approved_by
+});
+
 pub(crate) async fn get(
 	Query(params): Query<Params>,
 	State(GlobalState { pool }): State<GlobalState>,
@@ -58,7 +68,7 @@ pub(crate) async fn get(
 		query
 			.push(" WHERE ")
 			.push(" map.name LIKE ")
-			.push_bind(format!("%{map_name}%"));
+			.push_bind(format!("%{}%", escape_like(&map_name)));
 		multiple_filters = true;
 	}
 
@@ -133,5 +143,6 @@ pub(crate) async fn get(
 	Ok(Json(ResponseBody {
 		result,
 		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
 	}))
 }
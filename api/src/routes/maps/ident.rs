@@ -5,7 +5,7 @@ use {
 		extract::{Path, State},
 		Json,
 	},
-	database::schemas::account_id_to_steam_id64,
+	database::{crd::read::escape_like, schemas::account_id_to_steam_id64},
 	gokz_rs::prelude::*,
 	log::debug,
 	sqlx::QueryBuilder,
@@ -74,7 +74,7 @@ pub(crate) async fn get(
 		MapIdentifier::Name(map_name) => {
 			query
 				.push("map.name LIKE ")
-				.push_bind(format!("%{map_name}%"))
+				.push_bind(format!("%{}%", escape_like(&map_name)))
 				.push(" LIMIT 1) AS map");
 		}
 	};
@@ -114,5 +114,6 @@ pub(crate) async fn get(
 	Ok(Json(ResponseBody {
 		result,
 		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
 	}))
 }
@@ -1,6 +1,6 @@
 use {
 	super::{Course, Map, MapRow},
-	crate::{GlobalState, Response, ResponseBody},
+	crate::{cursor, openapi::schema, GlobalState, Response, ResponseBody},
 	axum::{
 		extract::{Query, State},
 		Json,
@@ -22,8 +22,20 @@ pub(crate) struct Params {
 	created_by: Option<String>,
 	approved_by: Option<String>,
 	limit: Option<u32>,
+	cursor: Option<String>,
 }
 
+schema!(Params {
+	name,
+	tier,
+	stage,
+	validated,
+	created_by,
+	approved_by,
+	limit,
+	cursor
+});
+
 pub(crate) async fn get(
 	Query(params): Query<Params>,
 	State(GlobalState { pool }): State<GlobalState>,
@@ -69,7 +81,7 @@ pub(crate) async fn get(
 		query
 			.push(" WHERE ")
 			.push(" map.name LIKE ")
-			.push_bind(format!("%{map_name}%"));
+			.push_bind(format!("%{}%", escape_like(&map_name)));
 		multiple_filters = true;
 	}
 
@@ -78,6 +90,7 @@ pub(crate) async fn get(
 			.push(if multiple_filters { " AND " } else { " WHERE " })
 			.push(" map.courses = ")
 			.push_bind(courses);
+		multiple_filters = true;
 	}
 
 	if let Some(validated) = params.validated {
@@ -85,6 +98,7 @@ pub(crate) async fn get(
 			.push(if multiple_filters { " AND " } else { " WHERE " })
 			.push(" map.validated = ")
 			.push_bind(validated);
+		multiple_filters = true;
 	}
 
 	if let Some(created_by) = params.created_by {
@@ -94,6 +108,7 @@ pub(crate) async fn get(
 			.push(if multiple_filters { " AND " } else { " WHERE " })
 			.push(" map.created_by = ")
 			.push_bind(player.id);
+		multiple_filters = true;
 	}
 
 	if let Some(approved_by) = params.approved_by {
@@ -103,15 +118,48 @@ pub(crate) async fn get(
 			.push(if multiple_filters { " AND " } else { " WHERE " })
 			.push(" map.approved_by = ")
 			.push_bind(player.id);
+		multiple_filters = true;
+	}
+
+	let tier = params
+		.tier
+		.map(Tier::try_from)
+		.transpose()?;
+
+	if let Some(tier) = tier {
+		query
+			.push(if multiple_filters { " AND " } else { " WHERE " })
+			.push(" EXISTS (SELECT 1 FROM courses AS c WHERE c.map_id = map.id AND c.kzt_difficulty = ")
+			.push_bind(tier as u8)
+			.push(")");
+		multiple_filters = true;
 	}
 
+	if let Some(cursor) = params.cursor {
+		let [name, id] = cursor::decode::<2>(&cursor)?;
+		let id = id
+			.parse::<u16>()
+			.map_err(|_| cursor::invalid(&cursor))?;
+
+		query
+			.push(if multiple_filters { " AND " } else { " WHERE " })
+			.push(" (map.name > ")
+			.push_bind(name.clone())
+			.push(" OR (map.name = ")
+			.push_bind(name)
+			.push(" AND map.id > ")
+			.push_bind(id)
+			.push("))");
+	}
+
+	let limit = params
+		.limit
+		.map_or(1500, |limit| limit.min(1500));
+
 	query
+		.push(" ORDER BY map.name, map.id ")
 		.push(" LIMIT ")
-		.push_bind(
-			params
-				.limit
-				.map_or(1500, |limit| limit.min(1500)),
-		)
+		.push_bind(limit)
 		.push(
 			r#"
 			) AS map
@@ -119,8 +167,7 @@ pub(crate) async fn get(
 			"#,
 		);
 
-	if let Some(tier) = params.tier {
-		let tier = Tier::try_from(tier)?;
+	if let Some(tier) = tier {
 		query
 			.push(" AND c.kzt_difficulty = ")
 			.push_bind(tier as u8);
@@ -131,7 +178,7 @@ pub(crate) async fn get(
 		JOIN players AS mapper ON mapper.id = map.created_by
 		JOIN players AS approver ON approver.id = map.approved_by
 		GROUP BY map.id
-		ORDER BY map.name
+		ORDER BY map.name, map.id
 		"#,
 	);
 
@@ -144,6 +191,11 @@ pub(crate) async fn get(
 		return Err(sqlx::Error::RowNotFound.into());
 	}
 
+	let next_cursor = result
+		.last()
+		.filter(|_| result.len() as u32 == limit)
+		.map(|map_row| cursor::encode(&[&map_row.name, &map_row.id.to_string()]));
+
 	let result = result
 		.into_iter()
 		.filter_map(|map_row| {
@@ -168,5 +220,6 @@ pub(crate) async fn get(
 	Ok(Json(ResponseBody {
 		result,
 		took: (Instant::now() - start).as_nanos(),
+		next_cursor,
 	}))
 }
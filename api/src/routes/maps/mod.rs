@@ -1,5 +1,8 @@
 use {
-	crate::ser_date::ser_date,
+	crate::{
+		openapi::{schema, Spec, MAP_IDENTIFIER, MODE_IDENTIFIER, PLAYER_IDENTIFIER},
+		ser_date::ser_date,
+	},
 	serde::{de::Error, Deserialize, Deserializer, Serialize},
 	sqlx::{types::time::PrimitiveDateTime, FromRow},
 };
@@ -13,6 +16,9 @@ pub(crate) use ident::get as ident;
 mod filters;
 pub(crate) use filters::get as filters;
 
+mod stats;
+pub(crate) use stats::get as stats;
+
 #[derive(Debug, Clone, FromRow)]
 pub(crate) struct MapRow {
 	pub(crate) id: u16,
@@ -43,6 +49,17 @@ pub struct Course {
 	pub(crate) vnl_difficulty: u8,
 }
 
+schema!(Course {
+	id,
+	stage,
+	kzt,
+	kzt_difficulty,
+	skz,
+	skz_difficulty,
+	vnl,
+	vnl_difficulty
+});
+
 #[derive(Debug, Serialize)]
 pub(crate) struct Map {
 	pub(crate) id: u16,
@@ -61,6 +78,21 @@ pub(crate) struct Map {
 	pub(crate) updated_on: PrimitiveDateTime,
 }
 
+schema!(Map {
+	id,
+	name,
+	tier,
+	courses,
+	validated,
+	mapper_name,
+	mapper_steam_id64,
+	approver_name,
+	approver_steam_id64,
+	filesize,
+	created_on,
+	updated_on
+});
+
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub(crate) struct FiltersRow {
 	pub(crate) courses: String,
@@ -80,6 +112,16 @@ pub(crate) struct Filter {
 	pub(crate) vnl: bool,
 }
 
+schema!(Filter {
+	map_name,
+	map_id,
+	stage,
+	course_id,
+	kzt,
+	skz,
+	vnl
+});
+
 pub fn number_to_bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
 where
 	D: Deserializer<'de>,
@@ -93,3 +135,26 @@ where
 		Err(Error::custom(crate::Error::JSON))
 	}
 }
+
+pub(crate) fn docs(spec: &mut Spec) {
+	spec.get::<(), Map>("/api/maps/{ident}", "Get a map by an identifier.")
+		.path_param::<String>("ident", MAP_IDENTIFIER);
+	spec.get::<index::Params, Vec<Map>>("/api/maps", "Get maps.")
+		.query_param("name", "Part of the map's name.")
+		.query_param("stage", "Only maps with exactly this many courses.")
+		.query_param("created_by", PLAYER_IDENTIFIER)
+		.query_param("approved_by", PLAYER_IDENTIFIER);
+	spec.get::<filters::Params, Vec<Filter>>(
+		"/api/maps/filters",
+		"Get the mode filters of every course, i.e. which modes a course can be completed in.",
+	)
+	.query_param("name", "Part of the map's name.")
+	.query_param("mode", MODE_IDENTIFIER)
+	.query_param("created_by", PLAYER_IDENTIFIER)
+	.query_param("approved_by", PLAYER_IDENTIFIER);
+	spec.get::<(), stats::MapStats>(
+		"/api/maps/{ident}/stats",
+		"Get completion statistics for every course, mode and runtype of a map.",
+	)
+	.path_param::<String>("ident", MAP_IDENTIFIER);
+}
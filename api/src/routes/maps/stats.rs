@@ -0,0 +1,310 @@
+use {
+	crate::{openapi::schema, ser_date::ser_date, Error, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Path, State},
+		Json,
+	},
+	database::{
+		crd::read::get_map,
+		schemas::{account_id_to_steam_id64, FancyPlayer},
+	},
+	gokz_rs::prelude::*,
+	log::debug,
+	serde::Serialize,
+	sqlx::{types::time::PrimitiveDateTime, FromRow},
+	std::{
+		collections::{BTreeMap, HashMap},
+		time::Instant,
+	},
+};
+
+/// Identifies a single leaderboard of the map: `(course_id, mode_id, has_teleports)`.
+type LeaderboardKey = (u32, u8, bool);
+
+#[derive(Debug, FromRow)]
+struct CompletionsQuery {
+	course_id: u32,
+	stage: u8,
+	mode_id: u8,
+	mode: String,
+	has_teleports: bool,
+	completions: i64,
+	unique_players: i64,
+	first_completion: PrimitiveDateTime,
+	latest_completion: PrimitiveDateTime,
+}
+
+#[derive(Debug, FromRow)]
+struct PersonalBestQuery {
+	course_id: u32,
+	mode_id: u8,
+	has_teleports: bool,
+	time: f64,
+}
+
+#[derive(Debug, FromRow)]
+struct WorldRecordQuery {
+	id: u32,
+	course_id: u32,
+	mode_id: u8,
+	has_teleports: bool,
+	time: f64,
+	player_id: u32,
+	player_name: String,
+	player_is_banned: bool,
+	created_on: PrimitiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MapStats {
+	id: u16,
+	name: String,
+	courses: Vec<CourseStats>,
+}
+
+schema!(MapStats { id, name, courses });
+
+#[derive(Debug, Serialize)]
+pub struct CourseStats {
+	course_id: u32,
+	stage: u8,
+	mode: String,
+	has_teleports: bool,
+	completions: u32,
+	unique_players: u32,
+	average_time: f64,
+	median_time: f64,
+	percentiles: Percentiles,
+	world_record: Option<WorldRecord>,
+	#[serde(serialize_with = "ser_date")]
+	first_completion: PrimitiveDateTime,
+	#[serde(serialize_with = "ser_date")]
+	latest_completion: PrimitiveDateTime,
+}
+
+schema!(CourseStats {
+	course_id,
+	stage,
+	mode,
+	has_teleports,
+	completions,
+	unique_players,
+	average_time,
+	median_time,
+	percentiles,
+	world_record,
+	first_completion,
+	latest_completion
+});
+
+/// Percentiles of the personal best times on a leaderboard.
+#[derive(Debug, Serialize)]
+pub struct Percentiles {
+	p10: f64,
+	p25: f64,
+	p75: f64,
+	p90: f64,
+}
+
+schema!(Percentiles { p10, p25, p75, p90 });
+
+#[derive(Debug, Serialize)]
+pub struct WorldRecord {
+	id: u32,
+	player: FancyPlayer,
+	time: f64,
+	#[serde(serialize_with = "ser_date")]
+	created_on: PrimitiveDateTime,
+}
+
+schema!(WorldRecord {
+	id,
+	player,
+	time,
+	created_on
+});
+
+pub(crate) async fn get(
+	Path(map_ident): Path<String>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<MapStats> {
+	let start = Instant::now();
+	debug!("[maps::stats::get]");
+	debug!("> `map_ident`: {map_ident:#?}");
+	let map_ident = map_ident.parse::<MapIdentifier>()?;
+	debug!("> `map_ident`: {map_ident:#?}");
+
+	if let MapIdentifier::Name(map_name) = &map_ident {
+		if map_name.contains('&') {
+			return Err(Error::Input {
+				message: format!(
+					"Interpreted `{map_name}` as a map name. You probably meant to use a `?` instead of the first `&`."
+				),
+				expected: String::from("?` instead of `&"),
+			});
+		}
+	}
+
+	let map = get_map(map_ident, &pool).await?;
+
+	let completions = sqlx::query_as::<_, CompletionsQuery>(
+		r#"
+		SELECT
+		  r.course_id AS course_id,
+		  c.stage AS stage,
+		  r.mode_id AS mode_id,
+		  mode.name AS mode,
+		  r.teleports > 0 AS has_teleports,
+		  COUNT(*) AS completions,
+		  COUNT(DISTINCT r.player_id) AS unique_players,
+		  MIN(r.created_on) AS first_completion,
+		  MAX(r.created_on) AS latest_completion
+		FROM records AS r
+		JOIN courses AS c ON c.id = r.course_id AND c.map_id = ?
+		JOIN modes AS mode ON mode.id = r.mode_id
+		GROUP BY r.course_id, r.mode_id, has_teleports
+		ORDER BY c.stage ASC, r.mode_id ASC, has_teleports ASC
+		"#,
+	)
+	.bind(map.id)
+	.fetch_all(&pool)
+	.await?;
+
+	if completions.is_empty() {
+		return Err(sqlx::Error::RowNotFound.into());
+	}
+
+	let personal_bests = sqlx::query_as::<_, PersonalBestQuery>(
+		r#"
+		SELECT
+		  r.course_id AS course_id,
+		  r.mode_id AS mode_id,
+		  r.teleports > 0 AS has_teleports,
+		  MIN(r.time) AS time
+		FROM records AS r
+		JOIN courses AS c ON c.id = r.course_id AND c.map_id = ?
+		GROUP BY r.course_id, r.mode_id, has_teleports, r.player_id
+		ORDER BY time ASC
+		"#,
+	)
+	.bind(map.id)
+	.fetch_all(&pool)
+	.await?
+	.into_iter()
+	.fold(HashMap::<LeaderboardKey, Vec<f64>>::new(), |mut leaderboards, pb| {
+		leaderboards
+			.entry((pb.course_id, pb.mode_id, pb.has_teleports))
+			.or_default()
+			.push(pb.time);
+		leaderboards
+	});
+
+	let mut world_records = BTreeMap::<LeaderboardKey, WorldRecord>::new();
+	for wr in sqlx::query_as::<_, WorldRecordQuery>(
+		r#"
+		SELECT
+		  r.id AS id,
+		  r.course_id AS course_id,
+		  r.mode_id AS mode_id,
+		  r.teleports > 0 AS has_teleports,
+		  r.time AS time,
+		  p.id AS player_id,
+		  p.name AS player_name,
+		  p.is_banned AS player_is_banned,
+		  r.created_on AS created_on
+		FROM records AS r
+		JOIN courses AS c ON c.id = r.course_id AND c.map_id = ?
+		JOIN players AS p ON p.id = r.player_id
+		WHERE (r.course_id, r.mode_id, r.teleports > 0, r.time) IN (
+		  SELECT
+		    r_inner.course_id,
+		    r_inner.mode_id,
+		    r_inner.teleports > 0,
+		    MIN(r_inner.time)
+		  FROM records AS r_inner
+		  JOIN courses AS c ON c.id = r_inner.course_id AND c.map_id = ?
+		  GROUP BY r_inner.course_id, r_inner.mode_id, r_inner.teleports > 0
+		)
+		ORDER BY r.created_on ASC, r.id ASC
+		"#,
+	)
+	.bind(map.id)
+	.bind(map.id)
+	.fetch_all(&pool)
+	.await?
+	{
+		// the earliest record wins ties
+		world_records
+			.entry((wr.course_id, wr.mode_id, wr.has_teleports))
+			.or_insert_with(|| {
+				let steam_id64 = account_id_to_steam_id64(wr.player_id);
+				let steam_id = SteamID::from(steam_id64);
+				WorldRecord {
+					id: wr.id,
+					player: FancyPlayer {
+						id: wr.player_id,
+						name: wr.player_name,
+						steam_id: steam_id.to_string(),
+						steam_id64: steam_id64.to_string(),
+						is_banned: wr.player_is_banned,
+					},
+					time: wr.time,
+					created_on: wr.created_on,
+				}
+			});
+	}
+
+	let courses = completions
+		.into_iter()
+		.map(|course| {
+			let key = (course.course_id, course.mode_id, course.has_teleports);
+			let times = personal_bests
+				.get(&key)
+				.map(Vec::as_slice)
+				.unwrap_or_default();
+
+			CourseStats {
+				course_id: course.course_id,
+				stage: course.stage,
+				mode: course.mode,
+				has_teleports: course.has_teleports,
+				completions: course.completions as u32,
+				unique_players: course.unique_players as u32,
+				average_time: times.iter().sum::<f64>() / times.len().max(1) as f64,
+				median_time: percentile(times, 50.0),
+				percentiles: Percentiles {
+					p10: percentile(times, 10.0),
+					p25: percentile(times, 25.0),
+					p75: percentile(times, 75.0),
+					p90: percentile(times, 90.0),
+				},
+				world_record: world_records.remove(&key),
+				first_completion: course.first_completion,
+				latest_completion: course.latest_completion,
+			}
+		})
+		.collect();
+
+	let result = MapStats {
+		id: map.id,
+		name: map.name,
+		courses,
+	};
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
+
+/// Nearest-rank percentile of already sorted `times`.
+fn percentile(times: &[f64], percentile: f64) -> f64 {
+	if times.is_empty() {
+		return 0.0;
+	}
+
+	// the smallest time that is greater than or equal to `percentile`% of all times
+	let rank = (percentile / 100.0 * times.len() as f64).ceil() as usize;
+	times[rank.clamp(1, times.len()) - 1]
+}
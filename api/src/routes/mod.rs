@@ -1,11 +1,27 @@
+use crate::openapi::Spec;
+
 mod index;
 pub(crate) use index::get as index;
 
-mod twitch_info;
-pub(crate) use twitch_info::post as twitch_info;
+mod openapi;
+pub(crate) use openapi::get as openapi;
 
+pub(crate) mod bans;
+pub(crate) mod mappers;
 pub(crate) mod maps;
 pub(crate) mod modes;
 pub(crate) mod players;
 pub(crate) mod records;
 pub(crate) mod servers;
+pub(crate) mod twitch_info;
+
+pub(crate) fn docs(spec: &mut Spec) {
+	modes::docs(spec);
+	players::docs(spec);
+	servers::docs(spec);
+	maps::docs(spec);
+	mappers::docs(spec);
+	bans::docs(spec);
+	records::docs(spec);
+	twitch_info::docs(spec);
+}
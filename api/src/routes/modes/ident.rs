@@ -36,5 +36,6 @@ pub(crate) async fn get(
 	Ok(Json(ResponseBody {
 		result,
 		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
 	}))
 }
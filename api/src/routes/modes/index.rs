@@ -32,5 +32,6 @@ pub(crate) async fn get(State(GlobalState { pool }): State<GlobalState>) -> Resp
 	Ok(Json(ResponseBody {
 		result,
 		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
 	}))
 }
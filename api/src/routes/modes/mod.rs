@@ -1,4 +1,11 @@
-use {crate::ser_date::ser_date, serde::Serialize, sqlx::types::time::PrimitiveDateTime};
+use {
+	crate::{
+		openapi::{schema, Spec, MODE_IDENTIFIER},
+		ser_date::ser_date,
+	},
+	serde::Serialize,
+	sqlx::types::time::PrimitiveDateTime,
+};
 
 mod ident;
 pub(crate) use ident::get as ident;
@@ -15,3 +22,17 @@ pub struct Mode {
 	#[serde(serialize_with = "ser_date")]
 	pub created_on: PrimitiveDateTime,
 }
+
+schema!(Mode {
+	id,
+	name,
+	name_short,
+	name_long,
+	created_on
+});
+
+pub(crate) fn docs(spec: &mut Spec) {
+	spec.get::<(), Mode>("/api/modes/{ident}", "Get 1 mode by an identifier.")
+		.path_param::<String>("ident", MODE_IDENTIFIER);
+	spec.get::<(), Vec<Mode>>("/api/modes", "Get all modes.");
+}
@@ -0,0 +1,5 @@
+use {axum::Json, serde_json::Value};
+
+pub(crate) async fn get() -> Json<&'static Value> {
+	Json(crate::openapi::spec())
+}
@@ -0,0 +1,52 @@
+use {
+	crate::{
+		routes::bans::{Ban, BanQuery, BAN_QUERY},
+		GlobalState, Response, ResponseBody,
+	},
+	axum::{
+		extract::{Path, State},
+		Json,
+	},
+	database::crd::read::get_player,
+	gokz_rs::prelude::*,
+	log::debug,
+	sqlx::QueryBuilder,
+	std::time::Instant,
+};
+
+pub(crate) async fn get(
+	Path(player_ident): Path<String>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Vec<Ban>> {
+	let start = Instant::now();
+	debug!("[players::bans::get]");
+	debug!("> `player_ident`: {player_ident:#?}");
+	let player_ident = player_ident.parse::<PlayerIdentifier>()?;
+	debug!("> `player_ident`: {player_ident:#?}");
+
+	let player = get_player(player_ident, &pool).await?;
+
+	let mut query = QueryBuilder::new(BAN_QUERY);
+	query
+		.push(" WHERE b.player_id = ")
+		.push_bind(player.id)
+		.push(" ORDER BY b.created_on DESC, b.id DESC");
+
+	let result = query
+		.build_query_as::<BanQuery>()
+		.fetch_all(&pool)
+		.await?
+		.into_iter()
+		.map(Ban::from)
+		.collect::<Vec<_>>();
+
+	if result.is_empty() {
+		return Err(sqlx::Error::RowNotFound.into());
+	}
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
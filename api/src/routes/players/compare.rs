@@ -0,0 +1,274 @@
+use {
+	crate::{openapi::schema, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Query, State},
+		Json,
+	},
+	database::{
+		crd::read::get_player,
+		schemas::{account_id_to_steam_id64, FancyPlayer, PlayerRow},
+	},
+	gokz_rs::prelude::*,
+	log::debug,
+	serde::{Deserialize, Serialize},
+	sqlx::{FromRow, QueryBuilder},
+	std::{collections::BTreeMap, time::Instant},
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	a: String,
+	b: String,
+	mode: String,
+	has_teleports: Option<bool>,
+}
+
+schema!(Params {
+	a,
+	b,
+	mode,
+	has_teleports
+});
+
+#[derive(Debug, FromRow)]
+struct CourseComparisonQuery {
+	map_id: u16,
+	map_name: String,
+	course_id: u32,
+	stage: u8,
+	tier: u8,
+	a_record_id: u32,
+	a_time: f64,
+	b_record_id: u32,
+	b_time: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Comparison {
+	a: FancyPlayer,
+	b: FancyPlayer,
+	summary: ComparisonSummary,
+	courses: Vec<CourseComparison>,
+}
+
+schema!(Comparison {
+	a,
+	b,
+	summary,
+	courses
+});
+
+#[derive(Debug, Serialize)]
+pub struct ComparisonSummary {
+	a_faster: u32,
+	b_faster: u32,
+	tied: u32,
+	tiers: Vec<TierComparison>,
+}
+
+schema!(ComparisonSummary {
+	a_faster,
+	b_faster,
+	tied,
+	tiers
+});
+
+#[derive(Debug, Serialize)]
+pub struct TierComparison {
+	tier: u8,
+	courses: u32,
+	/// Average of `a.time - b.time` across all courses of this tier.
+	average_difference: f64,
+}
+
+schema!(TierComparison {
+	tier,
+	courses,
+	average_difference
+});
+
+#[derive(Debug, Serialize)]
+pub struct CourseComparison {
+	map_id: u16,
+	map_name: String,
+	course_id: u32,
+	stage: u8,
+	tier: u8,
+	a_record_id: u32,
+	a_time: f64,
+	b_record_id: u32,
+	b_time: f64,
+	/// `a_time - b_time`; negative if player `a` is faster.
+	difference: f64,
+}
+
+schema!(CourseComparison {
+	map_id,
+	map_name,
+	course_id,
+	stage,
+	tier,
+	a_record_id,
+	a_time,
+	b_record_id,
+	b_time,
+	difference
+});
+
+pub(crate) async fn get(
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Comparison> {
+	let start = Instant::now();
+	debug!("[players::compare::get]");
+	debug!("> `params`: {params:#?}");
+
+	let player_a = get_player(params.a.parse::<PlayerIdentifier>()?, &pool).await?;
+	let player_b = get_player(params.b.parse::<PlayerIdentifier>()?, &pool).await?;
+
+	let mode = params.mode.parse::<Mode>()?;
+	let mode_column = mode.short().to_lowercase();
+	let teleports = if params.has_teleports.unwrap_or(false) { ">" } else { "=" };
+
+	let personal_bests = |query: &mut QueryBuilder<'_, _>, player_id: u32| {
+		query
+			.push(
+				r#"
+				SELECT
+				  r.course_id,
+				  MIN(r.id) AS record_id,
+				  pb.time
+				FROM (
+				  SELECT course_id, MIN(time) AS time
+				  FROM records
+				  WHERE player_id =
+				"#,
+			)
+			.push_bind(player_id)
+			.push(" AND mode_id = ")
+			.push_bind(mode as u8)
+			.push(format!(" AND teleports {teleports} 0"))
+			.push(
+				r#"
+				  GROUP BY course_id
+				) AS pb
+				JOIN records AS r
+				  ON r.course_id = pb.course_id
+				  AND r.time = pb.time
+				  AND r.player_id =
+				"#,
+			)
+			.push_bind(player_id)
+			.push(" AND r.mode_id = ")
+			.push_bind(mode as u8)
+			.push(format!(" AND r.teleports {teleports} 0"))
+			.push(" GROUP BY r.course_id, pb.time ");
+	};
+
+	let mut query = QueryBuilder::new(format!(
+		r#"
+		SELECT
+		  map.id AS map_id,
+		  map.name AS map_name,
+		  c.id AS course_id,
+		  c.stage AS stage,
+		  c.{mode_column}_difficulty AS tier,
+		  a.record_id AS a_record_id,
+		  a.time AS a_time,
+		  b.record_id AS b_record_id,
+		  b.time AS b_time
+		FROM (
+		"#
+	));
+
+	personal_bests(&mut query, player_a.id);
+	query.push(") AS a JOIN (");
+	personal_bests(&mut query, player_b.id);
+	query.push(
+		r#"
+		) AS b ON b.course_id = a.course_id
+		JOIN courses AS c ON c.id = a.course_id
+		JOIN maps AS map ON map.id = c.map_id
+		ORDER BY map.name ASC, c.stage ASC
+		"#,
+	);
+
+	let query_result = query
+		.build_query_as::<CourseComparisonQuery>()
+		.fetch_all(&pool)
+		.await?;
+
+	if query_result.is_empty() {
+		return Err(sqlx::Error::RowNotFound.into());
+	}
+
+	let courses = query_result
+		.into_iter()
+		.map(|course| CourseComparison {
+			map_id: course.map_id,
+			map_name: course.map_name,
+			course_id: course.course_id,
+			stage: course.stage,
+			tier: course.tier,
+			a_record_id: course.a_record_id,
+			a_time: course.a_time,
+			b_record_id: course.b_record_id,
+			b_time: course.b_time,
+			difference: course.a_time - course.b_time,
+		})
+		.collect::<Vec<_>>();
+
+	let mut summary = ComparisonSummary {
+		a_faster: 0,
+		b_faster: 0,
+		tied: 0,
+		tiers: Vec::new(),
+	};
+
+	let mut tiers = BTreeMap::<u8, (u32, f64)>::new();
+	for course in &courses {
+		match course.difference {
+			difference if difference < 0.0 => summary.a_faster += 1,
+			difference if difference > 0.0 => summary.b_faster += 1,
+			_ => summary.tied += 1,
+		};
+
+		let (count, total_difference) = tiers.entry(course.tier).or_default();
+		*count += 1;
+		*total_difference += course.difference;
+	}
+
+	summary.tiers = tiers
+		.into_iter()
+		.map(|(tier, (courses, total_difference))| TierComparison {
+			tier,
+			courses,
+			average_difference: total_difference / courses as f64,
+		})
+		.collect();
+
+	let result = Comparison {
+		a: fancy_player(player_a),
+		b: fancy_player(player_b),
+		summary,
+		courses,
+	};
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
+
+fn fancy_player(player: PlayerRow) -> FancyPlayer {
+	let steam_id64 = account_id_to_steam_id64(player.id);
+	let steam_id = SteamID::from(steam_id64);
+	FancyPlayer {
+		id: player.id,
+		name: player.name,
+		steam_id: steam_id.to_string(),
+		steam_id64: steam_id64.to_string(),
+		is_banned: player.is_banned,
+	}
+}
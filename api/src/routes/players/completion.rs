@@ -1,5 +1,5 @@
 use {
-	crate::{GlobalState, Response, ResponseBody},
+	crate::{openapi::schema, GlobalState, Response, ResponseBody},
 	axum::{
 		extract::{Path, State},
 		Json,
@@ -38,6 +38,18 @@ pub(crate) struct Completion {
 	vnl_pro: u32,
 }
 
+schema!(Completion {
+	id,
+	name,
+	is_banned,
+	kzt_tp,
+	kzt_pro,
+	skz_tp,
+	skz_pro,
+	vnl_tp,
+	vnl_pro
+});
+
 pub(crate) async fn get(
 	Path(player_ident): Path<String>,
 	State(GlobalState { pool }): State<GlobalState>,
@@ -107,5 +119,6 @@ pub(crate) async fn get(
 	Ok(Json(ResponseBody {
 		result,
 		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
 	}))
 }
@@ -1,7 +1,7 @@
 use sqlx::{types::Decimal, FromRow, QueryBuilder};
 
 use {
-	crate::{GlobalState, Response, ResponseBody},
+	crate::{openapi::schema, GlobalState, Response, ResponseBody},
 	axum::{
 		extract::{Path, State},
 		Json,
@@ -37,6 +37,15 @@ pub struct Player {
 	records: RecordSummary,
 }
 
+schema!(Player {
+	id,
+	name,
+	steam_id,
+	steam_id64,
+	is_banned,
+	records
+});
+
 #[derive(Debug, Serialize)]
 pub struct RecordSummary {
 	total: u32,
@@ -45,12 +54,21 @@ pub struct RecordSummary {
 	vnl: RecordCount,
 }
 
+schema!(RecordSummary {
+	total,
+	kzt,
+	skz,
+	vnl
+});
+
 #[derive(Debug, Serialize)]
 pub struct RecordCount {
 	tp: u32,
 	pro: u32,
 }
 
+schema!(RecordCount { tp, pro });
+
 pub(crate) async fn get(
 	Path(player_ident): Path<String>,
 	State(GlobalState { pool }): State<GlobalState>,
@@ -122,5 +140,6 @@ pub(crate) async fn get(
 	Ok(Json(ResponseBody {
 		result,
 		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
 	}))
 }
@@ -1,5 +1,5 @@
 use {
-	crate::{GlobalState, Response, ResponseBody},
+	crate::{cursor, openapi::schema, GlobalState, Response, ResponseBody},
 	axum::{
 		extract::{Query, State},
 		Json,
@@ -16,8 +16,16 @@ pub(crate) struct Params {
 	is_banned: Option<bool>,
 	limit: Option<u32>,
 	offset: Option<i32>,
+	cursor: Option<String>,
 }
 
+schema!(Params {
+	is_banned,
+	limit,
+	offset,
+	cursor
+});
+
 pub(crate) async fn get(
 	Query(params): Query<Params>,
 	State(GlobalState { pool }): State<GlobalState>,
@@ -32,20 +40,35 @@ pub(crate) async fn get(
 		"#,
 	);
 
+	let mut multiple_filters = false;
+
 	if let Some(is_banned) = params.is_banned {
 		query
 			.push("WHERE p.is_banned = ")
 			.push_bind(is_banned);
+		multiple_filters = true;
+	}
+
+	if let Some(cursor) = params.cursor {
+		let [id] = cursor::decode::<1>(&cursor)?;
+		let id = id
+			.parse::<u32>()
+			.map_err(|_| cursor::invalid(&cursor))?;
+
+		query
+			.push(if multiple_filters { " AND " } else { " WHERE " })
+			.push(" p.id < ")
+			.push_bind(id);
 	}
 
+	let limit = params
+		.limit
+		.map_or(100, |limit| limit.min(500));
+
 	query
 		.push(" ORDER BY p.id DESC ")
 		.push(" LIMIT ")
-		.push_bind(
-			params
-				.limit
-				.map_or(100, |limit| limit.min(500)),
-		)
+		.push_bind(limit)
 		.push(" OFFSET ")
 		.push_bind(params.offset.unwrap_or(0));
 
@@ -56,8 +79,14 @@ pub(crate) async fn get(
 
 	debug!("> {result:#?}");
 
+	let next_cursor = result
+		.last()
+		.filter(|_| result.len() as u32 == limit)
+		.map(|player| cursor::encode(&[&player.id.to_string()]));
+
 	Ok(Json(ResponseBody {
 		result,
 		took: (Instant::now() - start).as_nanos(),
+		next_cursor,
 	}))
 }
@@ -1,3 +1,15 @@
+use {
+	crate::{
+		openapi::{schema, Spec, MAP_IDENTIFIER, MODE_IDENTIFIER, PLAYER_IDENTIFIER},
+		routes::bans::Ban,
+		Error,
+	},
+	database::schemas::{account_id_to_steam_id64, FancyPlayer, PlayerRow},
+	gokz_rs::prelude::{Mode, SteamID},
+	serde::Serialize,
+	sqlx::{types::Decimal, FromRow},
+};
+
 mod index;
 pub(crate) use index::get as index;
 
@@ -6,3 +18,196 @@ pub(crate) use ident::get as ident;
 
 mod completion;
 pub(crate) use completion::get as completion;
+
+mod points;
+pub(crate) use points::get as points;
+
+mod ranking;
+pub(crate) use ranking::get as ranking;
+
+mod progression;
+pub(crate) use progression::get as progression;
+
+mod unfinished;
+pub(crate) use unfinished::get as unfinished;
+
+mod compare;
+pub(crate) use compare::get as compare;
+
+mod search;
+pub(crate) use search::get as search;
+
+mod names;
+pub(crate) use names::get as names;
+
+mod bans;
+pub(crate) use bans::get as bans;
+
+#[derive(Debug, FromRow)]
+struct PointsQuery {
+	id: u32,
+	name: String,
+	is_banned: bool,
+	records: i64,
+	total: Decimal,
+	kzt_tp: Decimal,
+	kzt_pro: Decimal,
+	skz_tp: Decimal,
+	skz_pro: Decimal,
+	vnl_tp: Decimal,
+	vnl_pro: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlayerPoints {
+	rank: u32,
+	player: FancyPlayer,
+	records: u32,
+	total: u32,
+	kzt: PointsCount,
+	skz: PointsCount,
+	vnl: PointsCount,
+}
+
+schema!(PlayerPoints {
+	rank,
+	player,
+	records,
+	total,
+	kzt,
+	skz,
+	vnl
+});
+
+#[derive(Debug, Serialize)]
+pub struct PointsCount {
+	tp: u32,
+	pro: u32,
+}
+
+schema!(PointsCount { tp, pro });
+
+/// Selects the points of every player, grouped by player. The caller is expected to push
+/// additional filters (starting with `WHERE`) followed by `GROUP BY pt.player_id`.
+fn points_query() -> String {
+	format!(
+		r#"
+		SELECT
+		  p.id                                                   AS id,
+		  p.name                                                 AS name,
+		  p.is_banned                                            AS is_banned,
+		  COUNT(*)                                               AS records,
+		  SUM(pt.points)                                         AS total,
+		  SUM(IF(pt.mode_id = {kzt} AND pt.has_teleports, pt.points, 0))     AS kzt_tp,
+		  SUM(IF(pt.mode_id = {kzt} AND NOT pt.has_teleports, pt.points, 0)) AS kzt_pro,
+		  SUM(IF(pt.mode_id = {skz} AND pt.has_teleports, pt.points, 0))     AS skz_tp,
+		  SUM(IF(pt.mode_id = {skz} AND NOT pt.has_teleports, pt.points, 0)) AS skz_pro,
+		  SUM(IF(pt.mode_id = {vnl} AND pt.has_teleports, pt.points, 0))     AS vnl_tp,
+		  SUM(IF(pt.mode_id = {vnl} AND NOT pt.has_teleports, pt.points, 0)) AS vnl_pro
+		FROM points AS pt
+		JOIN players AS p ON p.id = pt.player_id
+		"#,
+		kzt = Mode::KZTimer as u8,
+		skz = Mode::SimpleKZ as u8,
+		vnl = Mode::Vanilla as u8,
+	)
+}
+
+impl PointsQuery {
+	/// `rank` is 1 + the amount of players with strictly more points, so tied players share a
+	/// rank.
+	fn into_points(self, rank: u32) -> Result<PlayerPoints, Error> {
+		let steam_id64 = account_id_to_steam_id64(self.id);
+		let steam_id = SteamID::from(steam_id64);
+
+		Ok(PlayerPoints {
+			rank,
+			player: FancyPlayer {
+				id: self.id,
+				name: self.name,
+				steam_id: steam_id.to_string(),
+				steam_id64: steam_id64.to_string(),
+				is_banned: self.is_banned,
+			},
+			records: self.records as u32,
+			total: sum(self.total)?,
+			kzt: PointsCount {
+				tp: sum(self.kzt_tp)?,
+				pro: sum(self.kzt_pro)?,
+			},
+			skz: PointsCount {
+				tp: sum(self.skz_tp)?,
+				pro: sum(self.skz_pro)?,
+			},
+			vnl: PointsCount {
+				tp: sum(self.vnl_tp)?,
+				pro: sum(self.vnl_pro)?,
+			},
+		})
+	}
+}
+
+/// `SUM` returns a `DECIMAL` in MySQL, which can't be decoded as an integer directly.
+fn sum(sum: Decimal) -> Result<u32, Error> {
+	sum.try_into()
+		.map_err(|_| Error::Database {
+			message: format!("Invalid points total `{sum}`."),
+		})
+}
+
+pub(crate) fn docs(spec: &mut Spec) {
+	spec.get::<(), ident::Player>("/api/players/{ident}", "Get 1 player by an identifier.")
+		.path_param::<String>("ident", PLAYER_IDENTIFIER);
+	spec.get::<index::Params, Vec<PlayerRow>>("/api/players", "Get up to 500 players.");
+	spec.get::<(), completion::Completion>(
+		"/api/players/{ident}/completion",
+		"Get the amount of courses a player has finished, per mode and runtype.",
+	)
+	.path_param::<String>("ident", PLAYER_IDENTIFIER);
+	spec.get::<points::Params, PlayerPoints>(
+		"/api/players/{ident}/points",
+		"Get a player's points and global rank.",
+	)
+	.path_param::<String>("ident", PLAYER_IDENTIFIER)
+	.query_param("mode", MODE_IDENTIFIER);
+	spec.get::<ranking::Params, Vec<PlayerPoints>>(
+		"/api/players/ranking",
+		"Get players ranked by points.",
+	)
+	.query_param("mode", MODE_IDENTIFIER);
+	spec.get::<compare::Params, compare::Comparison>(
+		"/api/players/compare",
+		"Compare the personal bests of two players on every course both of them have finished.",
+	)
+	.query_param("a", PLAYER_IDENTIFIER)
+	.query_param("b", PLAYER_IDENTIFIER)
+	.query_param("mode", MODE_IDENTIFIER);
+	spec.get::<search::Params, Vec<search::SearchResult>>(
+		"/api/players/search",
+		"Search for players by name. Results are ranked by exact matches, prefix matches, substring matches and edit distance.",
+	)
+	.query_param("q", "The name to search for.");
+	spec.get::<progression::Params, Vec<progression::PersonalBest>>(
+		"/api/players/{ident}/progression",
+		"Get every record that improved a player's personal best on a course (sorted by date).",
+	)
+	.path_param::<String>("ident", PLAYER_IDENTIFIER)
+	.query_param("map", MAP_IDENTIFIER)
+	.query_param("mode", MODE_IDENTIFIER);
+	spec.get::<unfinished::Params, Vec<unfinished::UnfinishedCourse>>(
+		"/api/players/{ident}/unfinished",
+		"Get courses a player has not finished yet (sorted by tier).",
+	)
+	.path_param::<String>("ident", PLAYER_IDENTIFIER)
+	.query_param("mode", MODE_IDENTIFIER);
+	spec.get::<(), Vec<names::PlayerName>>(
+		"/api/players/{ident}/names",
+		"Get every name a player has been seen with, most recent first. Old names can also be used as an identifier for any player route.",
+	)
+	.path_param::<String>("ident", PLAYER_IDENTIFIER);
+	spec.get::<(), Vec<Ban>>(
+		"/api/players/{ident}/bans",
+		"Get every ban of a player, most recent first.",
+	)
+	.path_param::<String>("ident", PLAYER_IDENTIFIER);
+}
@@ -0,0 +1,69 @@
+use {
+	crate::{openapi::schema, ser_date::ser_date, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Path, State},
+		Json,
+	},
+	database::{crd::read::get_player, schemas::PlayerNameRow},
+	gokz_rs::prelude::*,
+	log::debug,
+	serde::Serialize,
+	sqlx::types::time::PrimitiveDateTime,
+	std::time::Instant,
+};
+
+#[derive(Debug, Serialize)]
+pub struct PlayerName {
+	name: String,
+	#[serde(serialize_with = "ser_date")]
+	first_seen: PrimitiveDateTime,
+	#[serde(serialize_with = "ser_date")]
+	last_seen: PrimitiveDateTime,
+}
+
+schema!(PlayerName {
+	name,
+	first_seen,
+	last_seen
+});
+
+pub(crate) async fn get(
+	Path(player_ident): Path<String>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Vec<PlayerName>> {
+	let start = Instant::now();
+	debug!("[players::names::get]");
+	debug!("> `player_ident`: {player_ident:#?}");
+	let player_ident = player_ident.parse::<PlayerIdentifier>()?;
+	debug!("> `player_ident`: {player_ident:#?}");
+
+	let player = get_player(player_ident, &pool).await?;
+
+	let result = sqlx::query_as::<_, PlayerNameRow>(
+		r#"
+		SELECT * FROM player_names
+		WHERE player_id = ?
+		ORDER BY last_seen DESC, first_seen DESC
+		"#,
+	)
+	.bind(player.id)
+	.fetch_all(&pool)
+	.await?
+	.into_iter()
+	.map(|row| PlayerName {
+		name: row.name,
+		first_seen: row.first_seen,
+		last_seen: row.last_seen,
+	})
+	.collect::<Vec<_>>();
+
+	if result.is_empty() {
+		return Err(sqlx::Error::RowNotFound.into());
+	}
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
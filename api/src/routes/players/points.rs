@@ -0,0 +1,115 @@
+use {
+	super::{points_query, PlayerPoints, PointsQuery},
+	crate::{openapi::schema, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Path, Query, State},
+		Json,
+	},
+	database::crd::read::get_player,
+	gokz_rs::prelude::*,
+	log::debug,
+	serde::Deserialize,
+	sqlx::QueryBuilder,
+	std::time::Instant,
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	mode: Option<String>,
+	has_teleports: Option<bool>,
+}
+
+schema!(Params {
+	mode,
+	has_teleports
+});
+
+pub(crate) async fn get(
+	Path(player_ident): Path<String>,
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<PlayerPoints> {
+	let start = Instant::now();
+	debug!("[players::points::get]");
+	debug!("> `player_ident`: {player_ident:#?}");
+	let player_ident = player_ident.parse::<PlayerIdentifier>()?;
+	debug!("> `player_ident`: {player_ident:#?}");
+	debug!("> `params`: {params:#?}");
+
+	let player = get_player(player_ident, &pool).await?;
+
+	let mode_id = match params.mode {
+		Some(mode) => Some(mode.parse::<Mode>()? as u8),
+		None => None,
+	};
+
+	let mut query = QueryBuilder::new(points_query());
+	query
+		.push(" WHERE pt.player_id = ")
+		.push_bind(player.id);
+
+	if let Some(mode_id) = mode_id {
+		query
+			.push(" AND pt.mode_id = ")
+			.push_bind(mode_id);
+	}
+
+	if let Some(has_teleports) = params.has_teleports {
+		query
+			.push(" AND pt.has_teleports = ")
+			.push_bind(has_teleports);
+	}
+
+	query.push(" GROUP BY pt.player_id ");
+
+	let points = query
+		.build_query_as::<PointsQuery>()
+		.fetch_one(&pool)
+		.await?;
+
+	let mut query = QueryBuilder::new(
+		r#"
+		SELECT COUNT(*) FROM (
+		  SELECT SUM(pt.points) AS total
+		  FROM points AS pt
+		  WHERE TRUE
+		"#,
+	);
+
+	if let Some(mode_id) = mode_id {
+		query
+			.push(" AND pt.mode_id = ")
+			.push_bind(mode_id);
+	}
+
+	if let Some(has_teleports) = params.has_teleports {
+		query
+			.push(" AND pt.has_teleports = ")
+			.push_bind(has_teleports);
+	}
+
+	query
+		.push(
+			r#"
+		  GROUP BY pt.player_id
+		  HAVING total >
+		"#,
+		)
+		.push_bind(points.total)
+		.push(") AS better_players");
+
+	let (better_players,) = query
+		.build_query_as::<(i64,)>()
+		.fetch_one(&pool)
+		.await?;
+
+	let result = points.into_points(better_players as u32 + 1)?;
+
+	debug!("> {result:#?}");
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
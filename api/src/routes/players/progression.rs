@@ -0,0 +1,179 @@
+use {
+	crate::openapi::schema,
+	crate::{
+		routes::{
+			maps::Course,
+			records::{Record, RecordQuery},
+		},
+		GlobalState, Response, ResponseBody,
+	},
+	axum::{
+		extract::{Path, Query, State},
+		Json,
+	},
+	database::{
+		crd::read::{get_map, get_player},
+		schemas::{account_id_to_steam_id64, FancyPlayer},
+	},
+	gokz_rs::prelude::*,
+	log::debug,
+	serde::{Deserialize, Serialize},
+	sqlx::QueryBuilder,
+	std::time::Instant,
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	map: String,
+	stage: Option<u8>,
+	mode: String,
+	has_teleports: Option<bool>,
+}
+
+schema!(Params {
+	map,
+	stage,
+	mode,
+	has_teleports
+});
+
+#[derive(Debug, Serialize)]
+pub struct PersonalBest {
+	#[serde(flatten)]
+	record: Record,
+	/// How much faster this record was than the previous personal best.
+	improvement: Option<f64>,
+}
+
+schema!(PersonalBest {
+	#[flatten]
+	record,
+	improvement
+});
+
+pub(crate) async fn get(
+	Path(player_ident): Path<String>,
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Vec<PersonalBest>> {
+	let start = Instant::now();
+	debug!("[players::progression::get]");
+	debug!("> `player_ident`: {player_ident:#?}");
+	let player_ident = player_ident.parse::<PlayerIdentifier>()?;
+	debug!("> `player_ident`: {player_ident:#?}");
+	debug!("> `params`: {params:#?}");
+
+	let player_id = get_player(player_ident, &pool)
+		.await
+		.map(|player_row| player_row.id)?;
+
+	let map_ident = params.map.parse::<MapIdentifier>()?;
+	let map_id = get_map(map_ident, &pool)
+		.await
+		.map(|map_row| map_row.id)?;
+
+	let mode_id = params.mode.parse::<Mode>()? as u8;
+	let has_teleports = params.has_teleports.unwrap_or(false);
+
+	let mut query = QueryBuilder::new(
+		r#"
+		SELECT
+		  r.id AS id,
+		  map.id AS map_id,
+		  map.name AS map_name,
+		  c.id AS course_id,
+		  c.stage AS stage,
+		  c.kzt AS kzt,
+		  c.kzt_difficulty AS kzt_difficulty,
+		  c.skz AS skz,
+		  c.skz_difficulty AS skz_difficulty,
+		  c.vnl AS vnl,
+		  c.vnl_difficulty AS vnl_difficulty,
+		  mode.name AS mode,
+		  p.id AS player_id,
+		  p.name AS player_name,
+		  p.is_banned AS player_is_banned,
+		  s.name AS server_name,
+		  r.time AS time,
+		  r.teleports AS teleports,
+		  r.created_on AS created_on
+		FROM records AS r
+		JOIN courses AS c ON c.id = r.course_id
+		JOIN maps AS map ON map.id = c.map_id
+		JOIN modes AS mode ON mode.id = r.mode_id
+		JOIN players AS p ON p.id = r.player_id
+		JOIN servers AS s ON s.id = r.server_id
+		WHERE r.player_id =
+		"#,
+	);
+
+	query
+		.push_bind(player_id)
+		.push(" AND c.map_id = ")
+		.push_bind(map_id)
+		.push(" AND c.stage = ")
+		.push_bind(params.stage.unwrap_or(0))
+		.push(" AND r.mode_id = ")
+		.push_bind(mode_id)
+		.push(format!(" AND r.teleports {} 0", if has_teleports { ">" } else { "=" }))
+		.push(" ORDER BY r.created_on ASC, r.id ASC ");
+
+	let query_result = query
+		.build_query_as::<RecordQuery>()
+		.fetch_all(&pool)
+		.await?;
+
+	let mut result = Vec::<PersonalBest>::new();
+	for record_query in query_result {
+		let previous_time = result
+			.last()
+			.map(|personal_best| personal_best.record.time);
+
+		if matches!(previous_time, Some(previous_time) if record_query.time >= previous_time) {
+			continue;
+		}
+
+		let steam_id64 = account_id_to_steam_id64(record_query.player_id);
+		let steam_id = SteamID::from(steam_id64);
+
+		result.push(PersonalBest {
+			improvement: previous_time.map(|previous_time| previous_time - record_query.time),
+			record: Record {
+				id: record_query.id,
+				map_name: record_query.map_name,
+				course: Course {
+					id: record_query.course_id,
+					stage: record_query.stage,
+					kzt: record_query.kzt,
+					kzt_difficulty: record_query.kzt_difficulty,
+					skz: record_query.skz,
+					skz_difficulty: record_query.skz_difficulty,
+					vnl: record_query.vnl,
+					vnl_difficulty: record_query.vnl_difficulty,
+				},
+				mode: record_query.mode,
+				player: FancyPlayer {
+					id: record_query.player_id,
+					name: record_query.player_name,
+					steam_id: steam_id.to_string(),
+					steam_id64: steam_id64.to_string(),
+					is_banned: record_query.player_is_banned,
+				},
+				server_name: record_query.server_name,
+				time: record_query.time,
+				teleports: record_query.teleports,
+				created_on: record_query.created_on,
+			},
+		});
+	}
+
+	if result.is_empty() {
+		return Err(sqlx::Error::RowNotFound.into());
+	}
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
@@ -0,0 +1,111 @@
+use {
+	super::{points_query, PlayerPoints, PointsQuery},
+	crate::{openapi::schema, Error, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Query, State},
+		Json,
+	},
+	gokz_rs::prelude::*,
+	log::debug,
+	serde::Deserialize,
+	sqlx::{FromRow, QueryBuilder},
+	std::time::Instant,
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	mode: Option<String>,
+	has_teleports: Option<bool>,
+	limit: Option<u32>,
+	offset: Option<u32>,
+}
+
+schema!(Params {
+	mode,
+	has_teleports,
+	limit,
+	offset
+});
+
+#[derive(Debug, FromRow)]
+struct RankedPointsQuery {
+	#[sqlx(flatten)]
+	points: PointsQuery,
+	player_rank: u64,
+}
+
+impl RankedPointsQuery {
+	fn into_points(self) -> Result<PlayerPoints, Error> {
+		self.points
+			.into_points(self.player_rank as u32)
+	}
+}
+
+pub(crate) async fn get(
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Vec<PlayerPoints>> {
+	let start = Instant::now();
+	debug!("[players::ranking::get]");
+	debug!("> `params`: {params:#?}");
+
+	let mut query =
+		QueryBuilder::new("SELECT *, RANK() OVER (ORDER BY total DESC) AS player_rank FROM (");
+	query.push(points_query());
+	let mut multiple_filters = false;
+
+	if let Some(mode) = params.mode {
+		let mode_id = mode.parse::<Mode>()? as u8;
+		query
+			.push(" WHERE pt.mode_id = ")
+			.push_bind(mode_id);
+		multiple_filters = true;
+	}
+
+	if let Some(has_teleports) = params.has_teleports {
+		query
+			.push(if multiple_filters { " AND " } else { " WHERE " })
+			.push(" pt.has_teleports = ")
+			.push_bind(has_teleports);
+	}
+
+	let offset = params.offset.unwrap_or(0);
+
+	query
+		.push(
+			r#"
+			GROUP BY pt.player_id
+			) AS totals
+			ORDER BY total DESC, id ASC
+			LIMIT
+			"#,
+		)
+		.push_bind(
+			params
+				.limit
+				.map_or(100, |limit| limit.min(500)),
+		)
+		.push(" OFFSET ")
+		.push_bind(offset);
+
+	let query_result = query
+		.build_query_as::<RankedPointsQuery>()
+		.fetch_all(&pool)
+		.await?;
+
+	if query_result.is_empty() {
+		return Err(sqlx::Error::RowNotFound.into());
+	}
+
+	// same definition as `/players/:ident/points`, so tied players share a rank
+	let result = query_result
+		.into_iter()
+		.map(RankedPointsQuery::into_points)
+		.collect::<Result<Vec<_>, _>>()?;
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
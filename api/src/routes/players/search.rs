@@ -0,0 +1,265 @@
+use {
+	crate::{
+		openapi::{schema, Schema, Spec},
+		Error, GlobalState, Response, ResponseBody,
+	},
+	axum::{
+		extract::{Query, State},
+		Json,
+	},
+	database::{
+		crd::read::escape_like,
+		schemas::{account_id_to_steam_id64, FancyPlayer},
+	},
+	gokz_rs::prelude::SteamID,
+	log::debug,
+	serde::{Deserialize, Serialize},
+	serde_json::{json, Value},
+	sqlx::FromRow,
+	std::time::Instant,
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	q: String,
+	limit: Option<u32>,
+}
+
+schema!(Params { q, limit });
+
+#[derive(Debug, FromRow)]
+struct CandidateQuery {
+	id: u32,
+	name: String,
+	is_banned: bool,
+	records: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+	Exact,
+	Prefix,
+	Substring,
+	Fuzzy,
+}
+
+impl Schema for MatchKind {
+	fn schema(_: &mut Spec) -> Value {
+		json!({ "type": "string", "enum": ["exact", "prefix", "substring", "fuzzy"] })
+	}
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResult {
+	player: FancyPlayer,
+	records: u32,
+	match_kind: MatchKind,
+	distance: usize,
+}
+
+schema!(SearchResult {
+	player,
+	records,
+	match_kind,
+	distance
+});
+
+/// Upper bound of candidates fetched from the database before ranking them.
+const MAX_CANDIDATES: u32 = 500;
+
+pub(crate) async fn get(
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Vec<SearchResult>> {
+	let start = Instant::now();
+	debug!("[players::search::get]");
+	debug!("> `params`: {params:#?}");
+
+	let search = params.q.trim().to_owned();
+	if search.is_empty() {
+		return Err(Error::Input {
+			message: String::from("Search query must not be empty."),
+			expected: String::from("a non-empty `q` parameter"),
+		});
+	}
+
+	// Candidates are everything that contains the search string, matches it as a word, or
+	// sounds like it. Prefix matches (which includes exact matches) can use `INDEX(name)`, so they
+	// are fetched first and the rest is filled up with everything else. The actual ranking happens
+	// below.
+	//
+	// Edit distance is only used for ranking, so fuzzy matches are limited to what `FULLTEXT` and
+	// `SOUNDS LIKE` come up with; a typo like `Alpah` for `Alpha` might not be found at all.
+	let prefix = format!("{}%", escape_like(&search));
+	let candidates = sqlx::query_as::<_, CandidateQuery>(
+		r#"
+		SELECT
+		  c.id AS id,
+		  c.name AS name,
+		  c.is_banned AS is_banned,
+		  (
+		    SELECT COUNT(*)
+		    FROM records AS r
+		    WHERE r.player_id = c.id
+		  ) AS records
+		FROM (
+		  (
+		    SELECT p.id, p.name, p.is_banned, 0 AS class
+		    FROM players AS p
+		    WHERE p.name LIKE ?
+		    ORDER BY p.name ASC
+		    LIMIT ?
+		  )
+		  UNION ALL
+		  (
+		    SELECT p.id, p.name, p.is_banned, 1 AS class
+		    FROM players AS p
+		    WHERE p.name NOT LIKE ?
+		    AND (
+		      p.name LIKE ?
+		      OR MATCH (p.name) AGAINST (? IN NATURAL LANGUAGE MODE)
+		      OR p.name SOUNDS LIKE ?
+		    )
+		    LIMIT ?
+		  )
+		) AS c
+		ORDER BY c.class ASC
+		LIMIT ?
+		"#,
+	)
+	.bind(&prefix)
+	.bind(MAX_CANDIDATES)
+	.bind(&prefix)
+	.bind(format!("%{}%", escape_like(&search)))
+	.bind(&search)
+	.bind(&search)
+	.bind(MAX_CANDIDATES)
+	.bind(MAX_CANDIDATES)
+	.fetch_all(&pool)
+	.await?;
+
+	let search = search.to_lowercase();
+	let mut ranked = candidates
+		.into_iter()
+		.map(|candidate| {
+			let name = candidate.name.to_lowercase();
+			let match_kind = if name == search {
+				MatchKind::Exact
+			} else if name.starts_with(&search) {
+				MatchKind::Prefix
+			} else if name.contains(&search) {
+				MatchKind::Substring
+			} else {
+				MatchKind::Fuzzy
+			};
+			let distance = edit_distance(&search, &name);
+			(match_kind, distance, candidate)
+		})
+		.collect::<Vec<_>>();
+
+	ranked.sort_by(|(a_kind, a_distance, a), (b_kind, b_distance, b)| {
+		a_kind
+			.cmp(b_kind)
+			.then(a_distance.cmp(b_distance))
+			.then(b.records.cmp(&a.records))
+			.then(a.id.cmp(&b.id))
+	});
+
+	let limit = params
+		.limit
+		.map_or(10, |limit| limit.min(100));
+
+	let result = ranked
+		.into_iter()
+		.take(limit as usize)
+		.map(|(match_kind, distance, candidate)| {
+			let steam_id64 = account_id_to_steam_id64(candidate.id);
+			let steam_id = SteamID::from(steam_id64);
+			SearchResult {
+				player: FancyPlayer {
+					id: candidate.id,
+					name: candidate.name,
+					steam_id: steam_id.to_string(),
+					steam_id64: steam_id64.to_string(),
+					is_banned: candidate.is_banned,
+				},
+				records: candidate.records as u32,
+				match_kind,
+				distance,
+			}
+		})
+		.collect::<Vec<_>>();
+
+	if result.is_empty() {
+		return Err(sqlx::Error::RowNotFound.into());
+	}
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
+
+/// Levenshtein distance between `a` and `b`, counted in `char`s.
+fn edit_distance(a: &str, b: &str) -> usize {
+	let b = b.chars().collect::<Vec<_>>();
+	let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+	for (i, a_char) in a.chars().enumerate() {
+		let mut previous = row[0];
+		row[0] = i + 1;
+		for (j, b_char) in b.iter().enumerate() {
+			let substitution = previous + usize::from(a_char != *b_char);
+			previous = row[j + 1];
+			row[j + 1] = substitution
+				.min(row[j] + 1)
+				.min(previous + 1);
+		}
+	}
+
+	row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::edit_distance;
+
+	#[test]
+	fn empty_strings() {
+		assert_eq!(edit_distance("", ""), 0);
+		assert_eq!(edit_distance("", "abc"), 3);
+		assert_eq!(edit_distance("abc", ""), 3);
+	}
+
+	#[test]
+	fn equal_strings() {
+		assert_eq!(edit_distance("alpha", "alpha"), 0);
+	}
+
+	#[test]
+	fn substitution() {
+		assert_eq!(edit_distance("alpha", "alpka"), 1);
+		assert_eq!(edit_distance("alpah", "alpha"), 2);
+	}
+
+	#[test]
+	fn insertion() {
+		assert_eq!(edit_distance("alpha", "alphas"), 1);
+		assert_eq!(edit_distance("lpha", "alpha"), 1);
+	}
+
+	#[test]
+	fn deletion() {
+		assert_eq!(edit_distance("alphas", "alpha"), 1);
+		assert_eq!(edit_distance("alpha", "aph"), 2);
+	}
+
+	#[test]
+	fn multibyte_chars_count_once() {
+		assert_eq!(edit_distance("jörg", "jorg"), 1);
+		assert_eq!(edit_distance("ñandú", "ñandu"), 1);
+		assert_eq!(edit_distance("日本", "日本語"), 1);
+	}
+}
@@ -0,0 +1,170 @@
+use {
+	crate::{openapi::schema, routes::maps::Course, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Path, Query, State},
+		Json,
+	},
+	database::crd::read::get_player,
+	gokz_rs::prelude::*,
+	log::debug,
+	serde::{Deserialize, Serialize},
+	sqlx::{FromRow, QueryBuilder},
+	std::time::Instant,
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	mode: String,
+	has_teleports: Option<bool>,
+	tier: Option<u8>,
+	stage: Option<u8>,
+}
+
+schema!(Params {
+	mode,
+	has_teleports,
+	tier,
+	stage
+});
+
+#[derive(Debug, FromRow)]
+struct UnfinishedQuery {
+	map_id: u16,
+	map_name: String,
+	course_id: u32,
+	stage: u8,
+	kzt: bool,
+	kzt_difficulty: u8,
+	skz: bool,
+	skz_difficulty: u8,
+	vnl: bool,
+	vnl_difficulty: u8,
+	tier: u8,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnfinishedCourse {
+	map_id: u16,
+	map_name: String,
+	tier: u8,
+	course: Course,
+}
+
+schema!(UnfinishedCourse {
+	map_id,
+	map_name,
+	tier,
+	course
+});
+
+pub(crate) async fn get(
+	Path(player_ident): Path<String>,
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Vec<UnfinishedCourse>> {
+	let start = Instant::now();
+	debug!("[players::unfinished::get]");
+	debug!("> `player_ident`: {player_ident:#?}");
+	let player_ident = player_ident.parse::<PlayerIdentifier>()?;
+	debug!("> `player_ident`: {player_ident:#?}");
+	debug!("> `params`: {params:#?}");
+
+	let player_id = get_player(player_ident, &pool)
+		.await
+		.map(|player_row| player_row.id)?;
+
+	let mode = params.mode.parse::<Mode>()?;
+	let mode_column = mode.short().to_lowercase();
+
+	let mut query = QueryBuilder::new(format!(
+		r#"
+		SELECT
+		  map.id AS map_id,
+		  map.name AS map_name,
+		  c.id AS course_id,
+		  c.stage AS stage,
+		  c.kzt AS kzt,
+		  c.kzt_difficulty AS kzt_difficulty,
+		  c.skz AS skz,
+		  c.skz_difficulty AS skz_difficulty,
+		  c.vnl AS vnl,
+		  c.vnl_difficulty AS vnl_difficulty,
+		  c.{mode_column}_difficulty AS tier
+		FROM courses AS c
+		JOIN maps AS map ON map.id = c.map_id
+		WHERE map.validated = 1
+		AND c.{mode_column} = 1
+		"#
+	));
+
+	if let Some(stage) = params.stage {
+		query
+			.push(" AND c.stage = ")
+			.push_bind(stage);
+	}
+
+	if let Some(tier) = params.tier {
+		let tier = Tier::try_from(tier)?;
+		query
+			.push(format!(" AND c.{mode_column}_difficulty = "))
+			.push_bind(tier as u8);
+	}
+
+	query
+		.push(
+			r#"
+			AND NOT EXISTS (
+			  SELECT 1 FROM records AS r
+			  WHERE r.course_id = c.id
+			  AND r.player_id =
+			"#,
+		)
+		.push_bind(player_id)
+		.push(" AND r.mode_id = ")
+		.push_bind(mode as u8);
+
+	if let Some(has_teleports) = params.has_teleports {
+		query.push(format!(" AND r.teleports {} 0", if has_teleports { ">" } else { "=" }));
+	}
+
+	query.push(
+		r#"
+		)
+		ORDER BY tier ASC, map.name ASC, c.stage ASC
+		"#,
+	);
+
+	let query_result = query
+		.build_query_as::<UnfinishedQuery>()
+		.fetch_all(&pool)
+		.await?;
+
+	if query_result.is_empty() {
+		return Err(sqlx::Error::RowNotFound.into());
+	}
+
+	let result = query_result
+		.into_iter()
+		.map(|unfinished| UnfinishedCourse {
+			map_id: unfinished.map_id,
+			map_name: unfinished.map_name,
+			tier: unfinished.tier,
+			course: Course {
+				id: unfinished.course_id,
+				stage: unfinished.stage,
+				kzt: unfinished.kzt,
+				kzt_difficulty: unfinished.kzt_difficulty,
+				skz: unfinished.skz,
+				skz_difficulty: unfinished.skz_difficulty,
+				vnl: unfinished.vnl,
+				vnl_difficulty: unfinished.vnl_difficulty,
+			},
+		})
+		.collect();
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
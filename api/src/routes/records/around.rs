@@ -0,0 +1,224 @@
+use {
+	super::{Record, RecordQuery},
+	crate::{openapi::schema, routes::maps::Course, Error, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Path, Query, State},
+		Json,
+	},
+	database::{
+		crd::read::{get_map, get_player},
+		schemas::{account_id_to_steam_id64, FancyPlayer},
+	},
+	gokz_rs::prelude::*,
+	log::debug,
+	serde::{Deserialize, Serialize},
+	sqlx::QueryBuilder,
+	std::{collections::HashMap, time::Instant},
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	mode: String,
+	stage: Option<u8>,
+	has_teleports: Option<bool>,
+	include_banned: Option<bool>,
+	limit: Option<u32>,
+}
+
+schema!(Params {
+	mode,
+	stage,
+	has_teleports,
+	include_banned,
+	limit
+});
+
+#[derive(Debug, Serialize)]
+pub struct RankedRecord {
+	place: u32,
+	#[serde(flatten)]
+	record: Record,
+}
+
+schema!(RankedRecord {
+	place,
+	#[flatten]
+	record
+});
+
+pub(crate) async fn get(
+	Path((map_ident, player_ident)): Path<(String, String)>,
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Vec<RankedRecord>> {
+	let start = Instant::now();
+	debug!("[records::around::get]");
+	debug!("> `map_ident`: {map_ident:#?}");
+	debug!("> `player_ident`: {player_ident:#?}");
+	let map_ident = map_ident.parse::<MapIdentifier>()?;
+	let player_ident = player_ident.parse::<PlayerIdentifier>()?;
+	debug!("> `map_ident`: {map_ident:#?}");
+	debug!("> `player_ident`: {player_ident:#?}");
+	debug!("> `params`: {params:#?}");
+
+	let map_id = get_map(map_ident, &pool)
+		.await
+		.map(|map_row| map_row.id)?;
+
+	let player_id = get_player(player_ident, &pool)
+		.await
+		.map(|player_row| player_row.id)?;
+
+	let mode_id = params.mode.parse::<Mode>()? as u8;
+	let stage = params.stage.unwrap_or(0);
+	let has_teleports = params.has_teleports.unwrap_or(false);
+	let limit = params
+		.limit
+		.map_or(5, |limit| limit.min(50)) as usize;
+
+	let mut query = QueryBuilder::new(
+		r#"
+		SELECT
+		  r.player_id,
+		  MIN(r.time) AS time
+		FROM records AS r
+		JOIN courses AS c ON c.id = r.course_id AND c.map_id =
+		"#,
+	);
+
+	query
+		.push_bind(map_id)
+		.push(" AND c.stage = ")
+		.push_bind(stage);
+
+	if !params.include_banned.unwrap_or(false) {
+		// the target player stays on the leaderboard even if they are banned
+		query
+			.push(" JOIN players AS p ON p.id = r.player_id AND (p.is_banned = 0 OR p.id = ")
+			.push_bind(player_id)
+			.push(") ");
+	}
+
+	query
+		.push(" WHERE r.mode_id = ")
+		.push_bind(mode_id)
+		.push(format!(" AND r.teleports {} 0", if has_teleports { ">" } else { "=" }))
+		.push(" GROUP BY r.player_id ORDER BY time ASC ");
+
+	let leaderboard = query
+		.build_query_as::<(u32, f64)>()
+		.fetch_all(&pool)
+		.await?;
+
+	let player_idx = leaderboard
+		.iter()
+		.position(|(id, _)| *id == player_id)
+		.ok_or(Error::NotFound {
+			message: String::from("Player has no record on this course."),
+		})?;
+
+	let slice = &leaderboard
+		[player_idx.saturating_sub(limit)..(player_idx + limit + 1).min(leaderboard.len())];
+
+	let places = leaderboard
+		.iter()
+		.enumerate()
+		.map(|(i, (id, _))| (*id, i as u32 + 1))
+		.collect::<HashMap<u32, u32>>();
+
+	let mut query = QueryBuilder::new(
+		r#"
+		SELECT
+		  r.id AS id,
+		  map.id AS map_id,
+		  map.name AS map_name,
+		  c.id AS course_id,
+		  c.stage AS stage,
+		  c.kzt AS kzt,
+		  c.kzt_difficulty AS kzt_difficulty,
+		  c.skz AS skz,
+		  c.skz_difficulty AS skz_difficulty,
+		  c.vnl AS vnl,
+		  c.vnl_difficulty AS vnl_difficulty,
+		  mode.name AS mode,
+		  p.id AS player_id,
+		  p.name AS player_name,
+		  p.is_banned AS player_is_banned,
+		  s.name AS server_name,
+		  r.time AS time,
+		  r.teleports AS teleports,
+		  r.created_on AS created_on
+		FROM records AS r
+		JOIN courses AS c ON c.id = r.course_id
+		JOIN maps AS map ON map.id = c.map_id
+		JOIN modes AS mode ON mode.id = r.mode_id
+		JOIN players AS p ON p.id = r.player_id
+		JOIN servers AS s ON s.id = r.server_id
+		WHERE c.map_id =
+		"#,
+	);
+
+	query
+		.push_bind(map_id)
+		.push(" AND c.stage = ")
+		.push_bind(stage)
+		.push(" AND r.mode_id = ")
+		.push_bind(mode_id)
+		.push(format!(" AND r.teleports {} 0", if has_teleports { ">" } else { "=" }))
+		.push(" AND (r.player_id, r.time) IN ")
+		.push_tuples(slice, |mut query, (player_id, time)| {
+			query
+				.push_bind(player_id)
+				.push_bind(time);
+		})
+		.push(" GROUP BY r.player_id ");
+
+	let query_result = query
+		.build_query_as::<RecordQuery>()
+		.fetch_all(&pool)
+		.await?;
+
+	let mut result = Vec::new();
+	for record_query in query_result {
+		let steam_id64 = account_id_to_steam_id64(record_query.player_id);
+		let steam_id = SteamID::from(steam_id64);
+
+		result.push(RankedRecord {
+			place: places[&record_query.player_id],
+			record: Record {
+				id: record_query.id,
+				map_name: record_query.map_name,
+				course: Course {
+					id: record_query.course_id,
+					stage: record_query.stage,
+					kzt: record_query.kzt,
+					kzt_difficulty: record_query.kzt_difficulty,
+					skz: record_query.skz,
+					skz_difficulty: record_query.skz_difficulty,
+					vnl: record_query.vnl,
+					vnl_difficulty: record_query.vnl_difficulty,
+				},
+				mode: record_query.mode,
+				player: FancyPlayer {
+					id: record_query.player_id,
+					name: record_query.player_name,
+					steam_id: steam_id.to_string(),
+					steam_id64: steam_id64.to_string(),
+					is_banned: record_query.player_is_banned,
+				},
+				server_name: record_query.server_name,
+				time: record_query.time,
+				teleports: record_query.teleports,
+				created_on: record_query.created_on,
+			},
+		});
+	}
+
+	result.sort_by_key(|ranked_record| ranked_record.place);
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
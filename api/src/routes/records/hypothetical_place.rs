@@ -0,0 +1,93 @@
+use {
+	super::place::{place_query, Leaderboard, PlaceQuery},
+	crate::{openapi::schema, Error, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Query, State},
+		Json,
+	},
+	database::crd::read::get_map,
+	gokz_rs::prelude::*,
+	log::debug,
+	serde::{Deserialize, Serialize},
+	std::time::Instant,
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	map: String,
+	stage: Option<u8>,
+	mode: String,
+	has_teleports: Option<bool>,
+	include_banned: Option<bool>,
+	time: f64,
+}
+
+schema!(Params {
+	map,
+	stage,
+	mode,
+	has_teleports,
+	include_banned,
+	time
+});
+
+#[derive(Debug, Serialize)]
+pub struct Place {
+	place: u32,
+	total: u32,
+}
+
+schema!(Place { place, total });
+
+pub(crate) async fn get(
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Place> {
+	let start = Instant::now();
+	debug!("[records::hypothetical_place::get]");
+	debug!("> `params`: {params:#?}");
+
+	if !params.time.is_finite() || params.time <= 0.0 {
+		return Err(Error::Input {
+			message: format!("`{}` is not a valid time.", params.time),
+			expected: String::from("a positive number of seconds"),
+		});
+	}
+
+	let map_ident = params.map.parse::<MapIdentifier>()?;
+	let map_id = get_map(map_ident, &pool)
+		.await
+		.map(|map_row| map_row.id)?;
+
+	let mode_id = params.mode.parse::<Mode>()? as u8;
+	let has_teleports = params.has_teleports.unwrap_or(false);
+
+	// same query as `/records/place/:id`, so both agree on the place of the same time
+	let place_query = place_query(
+		params.time,
+		Leaderboard {
+			map_id,
+			stage: params.stage.unwrap_or(0),
+			mode_id,
+			has_teleports,
+		},
+		params.include_banned.unwrap_or(false),
+		None,
+	)
+	.build_query_as::<PlaceQuery>()
+	.fetch_one(&pool)
+	.await?;
+
+	let result = Place {
+		place: place_query.place()?,
+		total: place_query.total as u32,
+	};
+
+	debug!("> {result:#?}");
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
@@ -1,6 +1,8 @@
 use {
 	super::{Record, RecordQuery},
-	crate::{routes::maps::Course, Error, GlobalState, Response, ResponseBody},
+	crate::{
+		cursor, openapi::schema, routes::maps::Course, Error, GlobalState, Response, ResponseBody,
+	},
 	axum::{
 		extract::{Query, State},
 		Json,
@@ -26,9 +28,24 @@ pub(crate) struct Params {
 	has_teleports: Option<bool>,
 	created_after: Option<String>,
 	created_before: Option<String>,
+	include_banned: Option<bool>,
 	limit: Option<u32>,
+	cursor: Option<String>,
 }
 
+schema!(Params {
+	mode,
+	stage,
+	map,
+	player,
+	has_teleports,
+	created_after,
+	created_before,
+	include_banned,
+	limit,
+	cursor
+});
+
 pub(crate) async fn get(
 	Query(params): Query<Params>,
 	State(GlobalState { pool }): State<GlobalState>,
@@ -65,6 +82,14 @@ pub(crate) async fn get(
 	);
 
 	let limit = params.limit.unwrap_or(100);
+	// a player's own banned runs are always shown when filtering for them
+	let include_banned = params.include_banned.unwrap_or(false) || params.player.is_some();
+
+	if !include_banned {
+		query.push(
+			" JOIN players AS p_inner ON p_inner.id = r_inner.player_id AND p_inner.is_banned = 0 ",
+		);
+	}
 
 	let no_params = params.mode.is_none()
 		&& params.stage.is_none()
@@ -73,11 +98,12 @@ pub(crate) async fn get(
 		&& params.has_teleports.is_none()
 		&& params.created_after.is_none()
 		&& params.created_before.is_none()
-		&& params.limit.is_none();
+		&& params.limit.is_none()
+		&& params.cursor.is_none();
 
 	if no_params {
 		query
-			.push(" ORDER BY r_inner.created_on DESC")
+			.push(" ORDER BY r_inner.created_on DESC, r_inner.id DESC")
 			.push(" LIMIT ")
 			.push_bind(limit)
 			.push(") AS r ");
@@ -183,10 +209,33 @@ pub(crate) async fn get(
 			query
 				.push(if multiple_filters { " AND " } else { " WHERE " })
 				.push(format!(" r_inner.teleports {} 0", if has_teleports { ">" } else { "=" }));
+
+			multiple_filters = true;
+		}
+
+		if let Some(cursor) = params.cursor {
+			let [created_on, id] = cursor::decode::<2>(&cursor)?;
+			let created_on = NaiveDateTime::parse_from_str(&created_on, "%Y-%m-%d %H:%M:%S")
+				.map_err(|_| cursor::invalid(&cursor))?
+				.format("%Y-%m-%d %H:%M:%S")
+				.to_string();
+			let id = id
+				.parse::<u32>()
+				.map_err(|_| cursor::invalid(&cursor))?;
+
+			query
+				.push(if multiple_filters { " AND " } else { " WHERE " })
+				.push(" (r_inner.created_on < ")
+				.push_bind(created_on.clone())
+				.push(" OR (r_inner.created_on = ")
+				.push_bind(created_on)
+				.push(" AND r_inner.id < ")
+				.push_bind(id)
+				.push("))");
 		}
 
 		query
-			.push(" ORDER BY r_inner.created_on DESC")
+			.push(" ORDER BY r_inner.created_on DESC, r_inner.id DESC")
 			.push(" LIMIT ")
 			.push_bind(limit)
 			.push(") AS r ");
@@ -199,7 +248,7 @@ pub(crate) async fn get(
 		JOIN modes AS mode ON mode.id = r.mode_id
 		JOIN players AS p ON p.id = r.player_id
 		JOIN servers AS s ON s.id = r.server_id
-		ORDER BY r.created_on DESC
+		ORDER BY r.created_on DESC, r.id DESC
 		"#,
 	);
 
@@ -212,6 +261,17 @@ pub(crate) async fn get(
 		return Err(sqlx::Error::RowNotFound.into());
 	}
 
+	let next_cursor = query_result
+		.last()
+		.filter(|_| query_result.len() as u32 == limit)
+		.map(|record_query| {
+			let created_on = record_query.created_on.to_string();
+			let (created_on, _) = created_on
+				.split_once('.')
+				.unwrap_or((&created_on, ""));
+			cursor::encode(&[created_on, &record_query.id.to_string()])
+		});
+
 	let mut result = Vec::new();
 	for record_query in query_result {
 		let steam_id64 = account_id_to_steam_id64(record_query.player_id);
@@ -248,5 +308,6 @@ pub(crate) async fn get(
 	Ok(Json(ResponseBody {
 		result,
 		took: (Instant::now() - start).as_nanos(),
+		next_cursor,
 	}))
 }
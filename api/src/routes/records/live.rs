@@ -0,0 +1,288 @@
+use {
+	super::Record,
+	crate::{openapi::schema, routes::maps::Course, Error, GlobalState},
+	axum::{
+		extract::{Query, State},
+		response::sse::{Event, KeepAlive, Sse},
+		Extension,
+	},
+	database::{
+		crd::read::{get_map, get_player},
+		schemas::{account_id_to_steam_id64, FancyPlayer},
+	},
+	gokz_rs::prelude::*,
+	log::{debug, error, info},
+	serde::{Deserialize, Serialize},
+	sqlx::{types::time::PrimitiveDateTime, FromRow, MySql, Pool},
+	std::{convert::Infallible, time::Duration},
+	tokio::sync::broadcast,
+	tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt},
+};
+
+/// How often the database is checked for new records.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Sending half of the live record feed. Every connected client holds a receiver.
+pub(crate) type LiveFeed = broadcast::Sender<LiveRecord>;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	mode: Option<String>,
+	map: Option<String>,
+	player: Option<String>,
+	only_wrs: Option<bool>,
+}
+
+schema!(Params {
+	mode,
+	map,
+	player,
+	only_wrs
+});
+
+#[derive(Debug, FromRow)]
+struct LiveRecordQuery {
+	id: u32,
+	map_id: u16,
+	map_name: String,
+	course_id: u32,
+	stage: u8,
+	kzt: bool,
+	kzt_difficulty: u8,
+	skz: bool,
+	skz_difficulty: u8,
+	vnl: bool,
+	vnl_difficulty: u8,
+	mode_id: u8,
+	mode: String,
+	player_id: u32,
+	player_name: String,
+	player_is_banned: bool,
+	server_name: String,
+	time: f64,
+	teleports: u32,
+	created_on: PrimitiveDateTime,
+	is_pb: bool,
+	is_wr: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct LiveRecord {
+	#[serde(flatten)]
+	record: Record,
+	is_pb: bool,
+	is_wr: bool,
+	#[serde(skip)]
+	map_id: u16,
+	#[serde(skip)]
+	mode_id: u8,
+}
+
+schema!(LiveRecord {
+	#[flatten]
+	record,
+	is_pb,
+	is_wr,
+	#[skip]
+	map_id,
+	#[skip]
+	mode_id
+});
+
+/// Spawns a task that watches the `records` table for rows inserted by `record_scraper` and
+/// broadcasts them to every connected client.
+pub(crate) async fn feed(pool: Pool<MySql>) -> Result<LiveFeed, sqlx::Error> {
+	let (sender, _) = broadcast::channel(256);
+	let (mut last_id,) = sqlx::query_as::<_, (Option<u32>,)>("SELECT MAX(id) FROM records")
+		.fetch_one(&pool)
+		.await?;
+
+	let feed = sender.clone();
+	tokio::spawn(async move {
+		loop {
+			tokio::time::sleep(POLL_INTERVAL).await;
+
+			let new_records = match fetch_new_records(last_id.unwrap_or(0), &pool).await {
+				Ok(new_records) => new_records,
+				Err(why) => {
+					error!("Failed to fetch new records: {why:?}");
+					continue;
+				}
+			};
+
+			for record in new_records {
+				last_id = Some(record.record.id);
+				// no subscribers is not an error
+				_ = feed.send(record);
+			}
+		}
+	});
+
+	info!("Started live record feed.");
+
+	Ok(sender)
+}
+
+async fn fetch_new_records(
+	last_id: u32,
+	pool: &Pool<MySql>,
+) -> Result<Vec<LiveRecord>, sqlx::Error> {
+	// Records are only compared against older records, so a PB / WR stays flagged as such even
+	// if it has been beaten since.
+	let records = sqlx::query_as::<_, LiveRecordQuery>(
+		r#"
+		SELECT
+		  r.id AS id,
+		  map.id AS map_id,
+		  map.name AS map_name,
+		  c.id AS course_id,
+		  c.stage AS stage,
+		  c.kzt AS kzt,
+		  c.kzt_difficulty AS kzt_difficulty,
+		  c.skz AS skz,
+		  c.skz_difficulty AS skz_difficulty,
+		  c.vnl AS vnl,
+		  c.vnl_difficulty AS vnl_difficulty,
+		  mode.id AS mode_id,
+		  mode.name AS mode,
+		  p.id AS player_id,
+		  p.name AS player_name,
+		  p.is_banned AS player_is_banned,
+		  s.name AS server_name,
+		  r.time AS time,
+		  r.teleports AS teleports,
+		  r.created_on AS created_on,
+		  NOT EXISTS (
+		    SELECT 1 FROM records AS r_old
+		    WHERE r_old.course_id = r.course_id
+		    AND r_old.mode_id = r.mode_id
+		    AND (r_old.teleports > 0) = (r.teleports > 0)
+		    AND r_old.player_id = r.player_id
+		    AND r_old.time <= r.time
+		    AND r_old.id < r.id
+		  ) AS is_pb,
+		  NOT EXISTS (
+		    SELECT 1 FROM records AS r_old
+		    JOIN players AS p_old ON p_old.id = r_old.player_id AND p_old.is_banned = 0
+		    WHERE r_old.course_id = r.course_id
+		    AND r_old.mode_id = r.mode_id
+		    AND (r_old.teleports > 0) = (r.teleports > 0)
+		    AND r_old.time <= r.time
+		    AND r_old.id < r.id
+		  ) AND p.is_banned = 0 AS is_wr
+		FROM records AS r
+		JOIN courses AS c ON c.id = r.course_id
+		JOIN maps AS map ON map.id = c.map_id
+		JOIN modes AS mode ON mode.id = r.mode_id
+		JOIN players AS p ON p.id = r.player_id
+		JOIN servers AS s ON s.id = r.server_id
+		WHERE r.id > ?
+		ORDER BY r.id ASC
+		LIMIT 500
+		"#,
+	)
+	.bind(last_id)
+	.fetch_all(pool)
+	.await?;
+
+	Ok(records
+		.into_iter()
+		.map(|record_query| {
+			let steam_id64 = account_id_to_steam_id64(record_query.player_id);
+			let steam_id = SteamID::from(steam_id64);
+
+			LiveRecord {
+				record: Record {
+					id: record_query.id,
+					map_name: record_query.map_name,
+					course: Course {
+						id: record_query.course_id,
+						stage: record_query.stage,
+						kzt: record_query.kzt,
+						kzt_difficulty: record_query.kzt_difficulty,
+						skz: record_query.skz,
+						skz_difficulty: record_query.skz_difficulty,
+						vnl: record_query.vnl,
+						vnl_difficulty: record_query.vnl_difficulty,
+					},
+					mode: record_query.mode,
+					player: FancyPlayer {
+						id: record_query.player_id,
+						name: record_query.player_name,
+						steam_id: steam_id.to_string(),
+						steam_id64: steam_id64.to_string(),
+						is_banned: record_query.player_is_banned,
+					},
+					server_name: record_query.server_name,
+					time: record_query.time,
+					teleports: record_query.teleports,
+					created_on: record_query.created_on,
+				},
+				is_pb: record_query.is_pb,
+				is_wr: record_query.is_wr,
+				map_id: record_query.map_id,
+				mode_id: record_query.mode_id,
+			}
+		})
+		.collect())
+}
+
+pub(crate) async fn get(
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+	Extension(live_feed): Extension<LiveFeed>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Error> {
+	debug!("[records::live::get]");
+	debug!("> `params`: {params:#?}");
+
+	let mode_id = match params.mode {
+		Some(mode) => Some(mode.parse::<Mode>()? as u8),
+		None => None,
+	};
+
+	let map_id = match params.map {
+		Some(map_ident) => {
+			let map_ident = map_ident.parse::<MapIdentifier>()?;
+			let map_id = get_map(map_ident, &pool)
+				.await
+				.map(|map_row| map_row.id)?;
+			Some(map_id)
+		}
+		None => None,
+	};
+
+	let player_id = match params.player {
+		Some(player_ident) => {
+			let player_ident = player_ident.parse::<PlayerIdentifier>()?;
+			let player_id = get_player(player_ident, &pool)
+				.await
+				.map(|player_row| player_row.id)?;
+			Some(player_id)
+		}
+		None => None,
+	};
+
+	let only_wrs = params.only_wrs.unwrap_or(false);
+
+	let stream = BroadcastStream::new(live_feed.subscribe()).filter_map(move |live_record| {
+		// lagging clients just miss some records
+		let live_record = live_record.ok()?;
+
+		let matches = mode_id.is_none_or(|mode_id| live_record.mode_id == mode_id)
+			&& map_id.is_none_or(|map_id| live_record.map_id == map_id)
+			&& player_id.is_none_or(|player_id| live_record.record.player.id == player_id)
+			&& (!only_wrs || live_record.is_wr);
+
+		if !matches {
+			return None;
+		}
+
+		Event::default()
+			.event("record")
+			.json_data(&live_record)
+			.ok()
+			.map(Ok)
+	});
+
+	Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
@@ -1,6 +1,6 @@
 use {
 	super::{Record, RecordQuery},
-	crate::{routes::maps::Course, Error, GlobalState, Response, ResponseBody},
+	crate::{openapi::schema, routes::maps::Course, Error, GlobalState, Response, ResponseBody},
 	axum::{
 		extract::{Path, Query, State},
 		Json,
@@ -27,9 +27,21 @@ pub(crate) struct Params {
 	has_teleports: Option<bool>,
 	created_after: Option<String>,
 	created_before: Option<String>,
+	include_banned: Option<bool>,
 	limit: Option<u32>,
 }
 
+schema!(Params {
+	mode,
+	stage,
+	player,
+	has_teleports,
+	created_after,
+	created_before,
+	include_banned,
+	limit
+});
+
 pub(crate) async fn get(
 	Path(map_ident): Path<String>,
 	Query(params): Query<Params>,
@@ -57,6 +69,8 @@ pub(crate) async fn get(
 		.await
 		.map(|map_row| map_row.id)?;
 
+	let include_banned = params.include_banned.unwrap_or(false);
+
 	let mut query = QueryBuilder::new(
 		r#"
 			SELECT
@@ -107,8 +121,10 @@ pub(crate) async fn get(
 			.push_bind(mode_id);
 	}
 
+	let mut player_id = None;
+
 	if let Some(player_ident) = params.player {
-		let player_id = match player_ident.parse::<PlayerIdentifier>()? {
+		let id = match player_ident.parse::<PlayerIdentifier>()? {
 			PlayerIdentifier::SteamID(steam_id) => steam_id_to_account_id(&steam_id.to_string())
 				.ok_or(Error::Input {
 					message: format!("Interpreted `{steam_id}` as a SteamID but it was invalid."),
@@ -122,7 +138,18 @@ pub(crate) async fn get(
 
 		query
 			.push(" JOIN players AS p ON p.id = ")
-			.push_bind(player_id);
+			.push_bind(id);
+
+		player_id = Some(id);
+	}
+
+	// only hide banned runs of other players than the one we're filtering for
+	if !include_banned {
+		query
+			.push(" JOIN players AS p_inner ON p_inner.id = r_inner.player_id ")
+			.push(" AND (p_inner.is_banned = 0 OR p_inner.id = ")
+			.push_bind(player_id)
+			.push(")");
 	}
 
 	let mut multiple_filters = false;
@@ -252,5 +279,6 @@ pub(crate) async fn get(
 	Ok(Json(ResponseBody {
 		result,
 		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
 	}))
 }
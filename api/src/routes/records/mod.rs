@@ -1,6 +1,11 @@
 use {
 	super::maps::Course,
-	crate::ser_date::ser_date,
+	crate::{
+		openapi::{
+			schema, Spec, INCLUDE_BANNED, MAP_IDENTIFIER, MODE_IDENTIFIER, PLAYER_IDENTIFIER,
+		},
+		ser_date::ser_date,
+	},
 	database::schemas::FancyPlayer,
 	serde::Serialize,
 	sqlx::{types::time::PrimitiveDateTime, FromRow},
@@ -18,9 +23,24 @@ pub(crate) use player::get as player;
 mod map;
 pub(crate) use map::get as map;
 
+mod around;
+pub(crate) use around::get as around;
+
 mod place;
 pub(crate) use place::get as place;
 
+mod hypothetical_place;
+pub(crate) use hypothetical_place::get as hypothetical_place;
+
+mod world_records;
+pub(crate) use world_records::get as world_records;
+
+mod wr_leaderboard;
+pub(crate) use wr_leaderboard::get as wr_leaderboard;
+
+mod live;
+pub(crate) use live::{feed as live_feed, get as live};
+
 #[derive(Debug, Clone, FromRow)]
 pub struct RecordQuery {
 	pub id: u32,
@@ -57,3 +77,88 @@ pub struct Record {
 	#[serde(serialize_with = "ser_date")]
 	pub created_on: PrimitiveDateTime,
 }
+
+schema!(Record {
+	id,
+	map_name,
+	course,
+	mode,
+	player,
+	server_name,
+	time,
+	teleports,
+	created_on
+});
+
+pub(crate) fn docs(spec: &mut Spec) {
+	spec.get::<(), Record>(
+		"/api/records/{id}",
+		"Get a record by its ID. This matches up with the GlobalAPI.",
+	)
+	.path_param::<u32>("id", "The record's ID.");
+	spec.get::<index::Params, Vec<Record>>("/api/records", "Get records (sorted by date).")
+		.query_param("mode", MODE_IDENTIFIER)
+		.query_param("map", MAP_IDENTIFIER)
+		.query_param("player", PLAYER_IDENTIFIER)
+		.query_param("created_after", "e.g. `2023-01-01T00:00:00`")
+		.query_param("created_before", "e.g. `2023-01-01T00:00:00`")
+		.query_param("include_banned", INCLUDE_BANNED);
+	spec.get::<player::Params, Vec<Record>>(
+		"/api/records/top/player/{ident}",
+		"Get player personal bests (sorted by date).",
+	)
+	.path_param::<String>("ident", PLAYER_IDENTIFIER)
+	.query_param("mode", MODE_IDENTIFIER)
+	.query_param("map", MAP_IDENTIFIER)
+	.query_param("created_after", "e.g. `2023-01-01T00:00:00`")
+	.query_param("created_before", "e.g. `2023-01-01T00:00:00`");
+	spec.get::<map::Params, Vec<Record>>("/api/records/top/map/{ident}", "Get map leaderboards.")
+		.path_param::<String>("ident", MAP_IDENTIFIER)
+		.query_param("mode", MODE_IDENTIFIER)
+		.query_param("player", PLAYER_IDENTIFIER)
+		.query_param("created_after", "e.g. `2023-01-01T00:00:00`")
+		.query_param("created_before", "e.g. `2023-01-01T00:00:00`")
+		.query_param("include_banned", INCLUDE_BANNED);
+	spec.get::<around::Params, Vec<around::RankedRecord>>(
+		"/api/records/top/map/{ident}/around/{player}",
+		"Get the records around a player's personal best on a map (sorted by place).",
+	)
+	.path_param::<String>("ident", MAP_IDENTIFIER)
+	.path_param::<String>("player", PLAYER_IDENTIFIER)
+	.query_param("mode", MODE_IDENTIFIER)
+	.query_param("include_banned", INCLUDE_BANNED);
+	spec.get::<world_records::Params, Vec<Record>>(
+		"/api/records/top/world_records",
+		"Get current world records (sorted by date).",
+	)
+	.query_param("mode", MODE_IDENTIFIER)
+	.query_param("map", MAP_IDENTIFIER)
+	.query_param("player", PLAYER_IDENTIFIER)
+	.query_param("include_banned", INCLUDE_BANNED);
+	spec.get::<wr_leaderboard::Params, Vec<wr_leaderboard::WorldRecordHolder>>(
+		"/api/records/top/wr_leaderboard",
+		"Get players ranked by the amount of world records they currently hold.",
+	)
+	.query_param("mode", MODE_IDENTIFIER)
+	.query_param("include_banned", INCLUDE_BANNED);
+	spec.get::<place::Params, u32>(
+		"/api/records/place/{id}",
+		"Get the place of a record on its leaderboard.",
+	)
+	.path_param::<u32>("id", "The record's ID.")
+	.query_param("include_banned", INCLUDE_BANNED);
+	spec.get::<hypothetical_place::Params, hypothetical_place::Place>(
+		"/api/records/place",
+		"Get the place a time would achieve on a map's leaderboard.",
+	)
+	.query_param("map", MAP_IDENTIFIER)
+	.query_param("mode", MODE_IDENTIFIER)
+	.query_param("include_banned", INCLUDE_BANNED);
+	spec.stream::<live::Params, live::LiveRecord>(
+		"/api/records/live",
+		"Stream newly submitted records as server-sent events. Every event is a record with two extra fields, `is_pb` and `is_wr`.",
+	)
+	.query_param("mode", MODE_IDENTIFIER)
+	.query_param("map", MAP_IDENTIFIER)
+	.query_param("player", PLAYER_IDENTIFIER);
+}
@@ -1,7 +1,7 @@
 use {
-	crate::{Error, GlobalState, Response, ResponseBody},
+	crate::{openapi::schema, Error, GlobalState, Response, ResponseBody},
 	axum::{
-		extract::{Path, State},
+		extract::{Path, Query, State},
 		Json,
 	},
 	database::{
@@ -9,119 +9,135 @@ use {
 		schemas::{CourseRow, RecordRow},
 	},
 	log::debug,
+	serde::Deserialize,
+	sqlx::{types::Decimal, FromRow, MySql, QueryBuilder},
 	std::time::Instant,
 };
 
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	include_banned: Option<bool>,
+}
+
+schema!(Params { include_banned });
+
 pub(crate) async fn get(
 	Path(record_id): Path<u32>,
+	Query(params): Query<Params>,
 	State(GlobalState { pool }): State<GlobalState>,
 ) -> Response<u32> {
 	let start = Instant::now();
 	debug!("[records::id::get]");
 	debug!("> `record_id`: {record_id:#?}");
+	debug!("> `params`: {params:#?}");
 
 	let RecordRow {
-		id,
 		course_id,
 		mode_id,
+		player_id,
+		time,
 		teleports,
 		..
 	} = get_record(record_id, &pool).await?;
 
 	let CourseRow { map_id, stage, .. } = get_course(course_id, &pool).await?;
 
-	let (if_no_teleports, if_teleports) = if teleports > 0 { (0, 1) } else { (1, 0) };
+	let include_banned = params.include_banned.unwrap_or(false);
+
+	let result = place_query(
+		time,
+		Leaderboard {
+			map_id,
+			stage,
+			mode_id,
+			has_teleports: teleports > 0,
+		},
+		include_banned,
+		Some(player_id),
+	)
+	.build_query_as::<PlaceQuery>()
+	.fetch_one(&pool)
+	.await?
+	.place()?;
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
+
+/// The leaderboard a place is calculated on.
+pub(super) struct Leaderboard {
+	pub(super) map_id: u16,
+	pub(super) stage: u8,
+	pub(super) mode_id: u8,
+	pub(super) has_teleports: bool,
+}
 
-	let result = sqlx::query!(
-		r#"
-		SELECT
-		  r.id AS id,
-		  map.id AS map_id,
-		  map.name AS map_name,
-		  c.id AS course_id,
-		  c.stage AS stage,
-		  c.kzt AS kzt,
-		  c.kzt_difficulty AS kzt_difficulty,
-		  c.skz AS skz,
-		  c.skz_difficulty AS skz_difficulty,
-		  c.vnl AS vnl,
-		  c.vnl_difficulty AS vnl_difficulty,
-		  mode.name AS mode,
-		  p.id AS player_id,
-		  p.name AS player_name,
-		  p.is_banned AS player_is_banned,
-		  s.name AS server_name,
-		  r.time AS time,
-		  r.teleports AS teleports,
-		  r.created_on AS created_on
+#[derive(Debug, FromRow)]
+pub(super) struct PlaceQuery {
+	faster: Decimal,
+	pub(super) total: i64,
+}
+
+impl PlaceQuery {
+	/// Players with the same time share a place.
+	pub(super) fn place(&self) -> Result<u32, Error> {
+		let faster: u32 = self
+			.faster
+			.try_into()
+			.map_err(|_| Error::Database {
+				message: format!("Invalid amount of faster records `{}`.", self.faster),
+			})?;
+
+		Ok(faster + 1)
+	}
+}
+
+/// Counts how many personal bests on `leaderboard` are faster than `time`, and how many there are
+/// in total. Banned players don't count, unless `include_banned` is set or they are `player_id`.
+pub(super) fn place_query(
+	time: f64,
+	leaderboard: Leaderboard,
+	include_banned: bool,
+	player_id: Option<u32>,
+) -> QueryBuilder<'static, MySql> {
+	let mut query = QueryBuilder::new("SELECT COALESCE(SUM(pb.time < ");
+
+	query
+		.push_bind(time)
+		.push(
+			r#"), 0) AS faster,
+		  COUNT(*) AS total
 		FROM (
 		  SELECT
-		    r_inner.mode_id,
-		    r_inner.course_id,
 		    r_inner.player_id,
-		    CASE WHEN r_inner.teleports = 0 THEN ? ELSE ? END AS has_teleports,
 		    MIN(r_inner.time) AS time
 		  FROM records AS r_inner
 		  JOIN courses AS c ON c.id = r_inner.course_id
-		    AND c.stage = ?
-		    AND c.map_id = ?
-		  JOIN modes AS mode ON mode.id = r_inner.mode_id AND mode.id = ?
-		  WHERE CASE WHEN r_inner.teleports = 0 THEN ? ELSE ? END
-		  GROUP BY
-		    r_inner.mode_id,
-		    r_inner.course_id,
-		    r_inner.player_id,
-		    has_teleports
+		    AND c.map_id =
+		  "#,
+		)
+		.push_bind(leaderboard.map_id)
+		.push(" AND c.stage = ")
+		.push_bind(leaderboard.stage)
+		.push(" JOIN players AS p_inner ON p_inner.id = r_inner.player_id AND (")
+		.push_bind(include_banned)
+		.push(" OR p_inner.is_banned = 0 OR p_inner.id = ")
+		.push_bind(player_id)
+		.push(") WHERE r_inner.mode_id = ")
+		.push_bind(leaderboard.mode_id)
+		.push(format!(
+			" AND r_inner.teleports {} 0",
+			if leaderboard.has_teleports { ">" } else { "=" }
+		))
+		.push(
+			r#"
+		  GROUP BY r_inner.player_id
 		) AS pb
-		JOIN records AS r ON r.mode_id = pb.mode_id
-		AND r.course_id = pb.course_id
-		AND r.player_id = pb.player_id
-		AND r.time = pb.time
-		JOIN courses AS c ON c.id = r.course_id
-		JOIN maps AS map ON map.id = c.map_id AND c.map_id = ? AND c.stage = ?
-		JOIN modes AS mode ON mode.id = r.mode_id AND r.mode_id = ?
-		JOIN players AS p ON p.id = r.player_id
-		JOIN servers AS s ON s.id = r.server_id
-		GROUP BY
-		  r.mode_id,
-		  r.course_id,
-		  r.player_id,
-		  pb.has_teleports
-		ORDER BY
-		  c.stage ASC,
-		  r.time,
-		  r.created_on DESC
 		"#,
-		if_no_teleports,
-		if_teleports,
-		stage,
-		map_id,
-		mode_id,
-		if teleports == 0 { if_no_teleports } else { if_teleports },
-		if teleports == 0 { if_teleports } else { if_no_teleports },
-		map_id,
-		stage,
-		mode_id
-	)
-	.fetch_all(&pool)
-	.await?;
-
-	let result = result
-		.into_iter()
-		.enumerate()
-		.find_map(|(i, record)| {
-			if record.id == id {
-				return Some(i as u32 + 1u32);
-			}
-			None
-		})
-		.ok_or(Error::Database {
-			// FIXME: This should never happen but sometimes it does.
-			message: String::from("No place found."),
-		})?;
+		);
 
-	Ok(Json(ResponseBody {
-		result,
-		took: (Instant::now() - start).as_nanos(),
-	}))
+	query
 }
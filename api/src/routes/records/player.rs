@@ -1,6 +1,6 @@
 use {
 	super::{Record, RecordQuery},
-	crate::{routes::maps::Course, Error, GlobalState, Response, ResponseBody},
+	crate::{openapi::schema, routes::maps::Course, Error, GlobalState, Response, ResponseBody},
 	axum::{
 		extract::{Path, Query, State},
 		Json,
@@ -28,6 +28,16 @@ pub(crate) struct Params {
 	limit: Option<u32>,
 }
 
+schema!(Params {
+	mode,
+	stage,
+	map,
+	has_teleports,
+	created_after,
+	created_before,
+	limit
+});
+
 pub(crate) async fn get(
 	Path(player_ident): Path<String>,
 	Query(params): Query<Params>,
@@ -315,5 +325,6 @@ pub(crate) async fn get(
 	Ok(Json(ResponseBody {
 		result,
 		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
 	}))
 }
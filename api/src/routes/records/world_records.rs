@@ -0,0 +1,241 @@
+use {
+	super::{Record, RecordQuery},
+	crate::{openapi::schema, routes::maps::Course, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Query, State},
+		Json,
+	},
+	database::{
+		crd::read::{get_map, get_player},
+		schemas::{account_id_to_steam_id64, FancyPlayer},
+	},
+	gokz_rs::prelude::*,
+	log::debug,
+	serde::Deserialize,
+	sqlx::QueryBuilder,
+	std::time::Instant,
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	mode: Option<String>,
+	stage: Option<u8>,
+	map: Option<String>,
+	tier: Option<u8>,
+	player: Option<String>,
+	has_teleports: Option<bool>,
+	include_banned: Option<bool>,
+	limit: Option<u32>,
+}
+
+schema!(Params {
+	mode,
+	stage,
+	map,
+	tier,
+	player,
+	has_teleports,
+	include_banned,
+	limit
+});
+
+pub(crate) async fn get(
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Vec<Record>> {
+	let start = Instant::now();
+	debug!("[records::world_records::get]");
+	debug!("> `params`: {params:#?}");
+
+	let include_banned = params.include_banned.unwrap_or(false);
+
+	let mut query = QueryBuilder::new(
+		r#"
+		SELECT
+		  r.id AS id,
+		  map.id AS map_id,
+		  map.name AS map_name,
+		  c.id AS course_id,
+		  c.stage AS stage,
+		  c.kzt AS kzt,
+		  c.kzt_difficulty AS kzt_difficulty,
+		  c.skz AS skz,
+		  c.skz_difficulty AS skz_difficulty,
+		  c.vnl AS vnl,
+		  c.vnl_difficulty AS vnl_difficulty,
+		  mode.name AS mode,
+		  p.id AS player_id,
+		  p.name AS player_name,
+		  p.is_banned AS player_is_banned,
+		  s.name AS server_name,
+		  r.time AS time,
+		  r.teleports AS teleports,
+		  r.created_on AS created_on
+		FROM (
+		  SELECT
+		    r_inner.mode_id,
+		    r_inner.course_id,
+		    CASE WHEN r_inner.teleports = 0 THEN 0 ELSE 1 END AS has_teleports,
+		    MIN(r_inner.time) AS time
+		  FROM records AS r_inner
+		  JOIN courses AS c ON c.id = r_inner.course_id
+		"#,
+	);
+
+	if let Some(stage) = params.stage {
+		query
+			.push(" AND c.stage = ")
+			.push_bind(stage);
+	}
+
+	if let Some(map_ident) = params.map {
+		let map_ident = map_ident.parse::<MapIdentifier>()?;
+		let map_id = get_map(map_ident, &pool)
+			.await
+			.map(|map_row| map_row.id)?;
+
+		query
+			.push(" AND c.map_id = ")
+			.push_bind(map_id);
+	}
+
+	let mode = params
+		.mode
+		.map(|mode| mode.parse::<Mode>())
+		.transpose()?;
+
+	if let Some(tier) = params.tier {
+		let tier = Tier::try_from(tier)?;
+		// every mode has its own difficulty
+		let tier_column = match mode {
+			Some(mode) => format!("c.{}_difficulty", mode.short().to_lowercase()),
+			None => format!(
+				"CASE r_inner.mode_id WHEN {} THEN c.skz_difficulty WHEN {} THEN c.vnl_difficulty \
+				 ELSE c.kzt_difficulty END",
+				Mode::SimpleKZ as u8,
+				Mode::Vanilla as u8
+			),
+		};
+
+		query
+			.push(format!(" AND {tier_column} = "))
+			.push_bind(tier as u8);
+	}
+
+	if !include_banned {
+		query.push(" JOIN players AS p ON p.id = r_inner.player_id AND p.is_banned = 0 ");
+	}
+
+	let mut multiple_filters = false;
+
+	if let Some(mode) = mode {
+		query
+			.push(" WHERE r_inner.mode_id = ")
+			.push_bind(mode as u8);
+		multiple_filters = true;
+	}
+
+	if let Some(has_teleports) = params.has_teleports {
+		query
+			.push(if multiple_filters { " AND " } else { " WHERE " })
+			.push(format!(" r_inner.teleports {} 0", if has_teleports { ">" } else { "=" }));
+	}
+
+	query.push(
+		r#"
+		  GROUP BY r_inner.mode_id, r_inner.course_id, has_teleports
+		) AS wr
+		JOIN records AS r
+		  ON r.mode_id = wr.mode_id
+		  AND r.course_id = wr.course_id
+		  AND r.time = wr.time
+		  AND CASE WHEN r.teleports = 0 THEN 0 ELSE 1 END = wr.has_teleports
+		JOIN courses AS c ON c.id = r.course_id
+		JOIN maps AS map ON map.id = c.map_id
+		JOIN modes AS mode ON mode.id = r.mode_id
+		JOIN players AS p ON p.id = r.player_id
+		JOIN servers AS s ON s.id = r.server_id
+		"#,
+	);
+
+	let mut multiple_filters = false;
+
+	if !include_banned {
+		query.push(" WHERE p.is_banned = 0 ");
+		multiple_filters = true;
+	}
+
+	if let Some(player_ident) = params.player {
+		let player_ident = player_ident.parse::<PlayerIdentifier>()?;
+		let player_id = get_player(player_ident, &pool)
+			.await
+			.map(|player_row| player_row.id)?;
+
+		query
+			.push(if multiple_filters { " AND " } else { " WHERE " })
+			.push(" r.player_id = ")
+			.push_bind(player_id);
+	}
+
+	let limit = params
+		.limit
+		.map_or(100, |limit| limit.min(1000));
+
+	query
+		.push(
+			r#"
+			GROUP BY r.mode_id, r.course_id, wr.has_teleports
+			ORDER BY r.created_on DESC
+			LIMIT
+			"#,
+		)
+		.push_bind(limit);
+
+	let query_result = query
+		.build_query_as::<RecordQuery>()
+		.fetch_all(&pool)
+		.await?;
+
+	if query_result.is_empty() {
+		return Err(sqlx::Error::RowNotFound.into());
+	}
+
+	let mut result = Vec::new();
+	for record_query in query_result {
+		let steam_id64 = account_id_to_steam_id64(record_query.player_id);
+		let steam_id = SteamID::from(steam_id64);
+
+		result.push(Record {
+			id: record_query.id,
+			map_name: record_query.map_name,
+			course: Course {
+				id: record_query.course_id,
+				stage: record_query.stage,
+				kzt: record_query.kzt,
+				kzt_difficulty: record_query.kzt_difficulty,
+				skz: record_query.skz,
+				skz_difficulty: record_query.skz_difficulty,
+				vnl: record_query.vnl,
+				vnl_difficulty: record_query.vnl_difficulty,
+			},
+			mode: record_query.mode,
+			player: FancyPlayer {
+				id: record_query.player_id,
+				name: record_query.player_name,
+				steam_id: steam_id.to_string(),
+				steam_id64: steam_id64.to_string(),
+				is_banned: record_query.player_is_banned,
+			},
+			server_name: record_query.server_name,
+			time: record_query.time,
+			teleports: record_query.teleports,
+			created_on: record_query.created_on,
+		});
+	}
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
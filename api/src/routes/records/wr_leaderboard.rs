@@ -0,0 +1,222 @@
+use {
+	crate::{openapi::schema, Error, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Query, State},
+		Json,
+	},
+	database::schemas::{account_id_to_steam_id64, FancyPlayer},
+	gokz_rs::prelude::*,
+	log::debug,
+	serde::{Deserialize, Serialize},
+	sqlx::{types::Decimal, FromRow, QueryBuilder},
+	std::time::Instant,
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	mode: Option<String>,
+	has_teleports: Option<bool>,
+	stage: Option<u8>,
+	include_banned: Option<bool>,
+	limit: Option<u32>,
+}
+
+schema!(Params {
+	mode,
+	has_teleports,
+	stage,
+	include_banned,
+	limit
+});
+
+#[derive(Debug, FromRow)]
+struct WorldRecordHolderQuery {
+	id: u32,
+	name: String,
+	is_banned: bool,
+	total: i64,
+	kzt_tp: Decimal,
+	kzt_pro: Decimal,
+	skz_tp: Decimal,
+	skz_pro: Decimal,
+	vnl_tp: Decimal,
+	vnl_pro: Decimal,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorldRecordHolder {
+	player: FancyPlayer,
+	total: u32,
+	kzt: WorldRecordCount,
+	skz: WorldRecordCount,
+	vnl: WorldRecordCount,
+}
+
+schema!(WorldRecordHolder {
+	player,
+	total,
+	kzt,
+	skz,
+	vnl
+});
+
+#[derive(Debug, Serialize)]
+pub struct WorldRecordCount {
+	tp: u32,
+	pro: u32,
+}
+
+schema!(WorldRecordCount { tp, pro });
+
+pub(crate) async fn get(
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Vec<WorldRecordHolder>> {
+	let start = Instant::now();
+	debug!("[records::wr_leaderboard::get]");
+	debug!("> `params`: {params:#?}");
+
+	let include_banned = params.include_banned.unwrap_or(false);
+
+	let mut query = QueryBuilder::new(format!(
+		r#"
+		SELECT
+		  p.id                                       AS id,
+		  p.name                                     AS name,
+		  p.is_banned                                AS is_banned,
+		  COUNT(*)                                   AS total,
+		  SUM(r.mode_id = {kzt} AND r.teleports > 0) AS kzt_tp,
+		  SUM(r.mode_id = {kzt} AND r.teleports = 0) AS kzt_pro,
+		  SUM(r.mode_id = {skz} AND r.teleports > 0) AS skz_tp,
+		  SUM(r.mode_id = {skz} AND r.teleports = 0) AS skz_pro,
+		  SUM(r.mode_id = {vnl} AND r.teleports > 0) AS vnl_tp,
+		  SUM(r.mode_id = {vnl} AND r.teleports = 0) AS vnl_pro
+		FROM (
+		  SELECT MIN(r_wr.id) AS id
+		  FROM (
+		    SELECT
+		      r_inner.mode_id,
+		      r_inner.course_id,
+		      CASE WHEN r_inner.teleports = 0 THEN 0 ELSE 1 END AS has_teleports,
+		      MIN(r_inner.time) AS time
+		    FROM records AS r_inner
+		    JOIN courses AS c ON c.id = r_inner.course_id
+		"#,
+		kzt = Mode::KZTimer as u8,
+		skz = Mode::SimpleKZ as u8,
+		vnl = Mode::Vanilla as u8,
+	));
+
+	if let Some(stage) = params.stage {
+		query
+			.push(" AND c.stage = ")
+			.push_bind(stage);
+	}
+
+	if !include_banned {
+		query.push(" JOIN players AS p ON p.id = r_inner.player_id AND p.is_banned = 0 ");
+	}
+
+	let mut multiple_filters = false;
+
+	if let Some(mode) = params.mode {
+		let mode_id = mode.parse::<Mode>()? as u8;
+		query
+			.push(" WHERE r_inner.mode_id = ")
+			.push_bind(mode_id);
+		multiple_filters = true;
+	}
+
+	if let Some(has_teleports) = params.has_teleports {
+		query
+			.push(if multiple_filters { " AND " } else { " WHERE " })
+			.push(format!(" r_inner.teleports {} 0", if has_teleports { ">" } else { "=" }));
+	}
+
+	query.push(
+		r#"
+		    GROUP BY r_inner.mode_id, r_inner.course_id, has_teleports
+		  ) AS wr
+		  JOIN records AS r_wr
+		    ON r_wr.mode_id = wr.mode_id
+		    AND r_wr.course_id = wr.course_id
+		    AND r_wr.time = wr.time
+		    AND CASE WHEN r_wr.teleports = 0 THEN 0 ELSE 1 END = wr.has_teleports
+		"#,
+	);
+
+	if !include_banned {
+		query.push(" JOIN players AS p ON p.id = r_wr.player_id AND p.is_banned = 0 ");
+	}
+
+	let limit = params
+		.limit
+		.map_or(100, |limit| limit.min(500));
+
+	query
+		.push(
+			r#"
+		  GROUP BY wr.mode_id, wr.course_id, wr.has_teleports
+		) AS wr_ids
+		JOIN records AS r ON r.id = wr_ids.id
+		JOIN players AS p ON p.id = r.player_id
+		GROUP BY p.id
+		ORDER BY total DESC, p.id ASC
+		LIMIT
+		"#,
+		)
+		.push_bind(limit);
+
+	let query_result = query
+		.build_query_as::<WorldRecordHolderQuery>()
+		.fetch_all(&pool)
+		.await?;
+
+	if query_result.is_empty() {
+		return Err(sqlx::Error::RowNotFound.into());
+	}
+
+	let result = query_result
+		.into_iter()
+		.map(|holder| {
+			let steam_id64 = account_id_to_steam_id64(holder.id);
+			let steam_id = SteamID::from(steam_id64);
+			Ok(WorldRecordHolder {
+				player: FancyPlayer {
+					id: holder.id,
+					name: holder.name,
+					steam_id: steam_id.to_string(),
+					steam_id64: steam_id64.to_string(),
+					is_banned: holder.is_banned,
+				},
+				total: holder.total as u32,
+				kzt: WorldRecordCount {
+					tp: count(holder.kzt_tp)?,
+					pro: count(holder.kzt_pro)?,
+				},
+				skz: WorldRecordCount {
+					tp: count(holder.skz_tp)?,
+					pro: count(holder.skz_pro)?,
+				},
+				vnl: WorldRecordCount {
+					tp: count(holder.vnl_tp)?,
+					pro: count(holder.vnl_pro)?,
+				},
+			})
+		})
+		.collect::<Result<Vec<_>, Error>>()?;
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
+
+/// `SUM` returns a `DECIMAL` in MySQL, which can't be decoded as an integer directly.
+fn count(sum: Decimal) -> Result<u32, Error> {
+	sum.try_into()
+		.map_err(|_| Error::Database {
+			message: format!("Invalid record count `{sum}`."),
+		})
+}
@@ -5,7 +5,10 @@ use {
 		extract::{Path, State},
 		Json,
 	},
-	database::schemas::{account_id_to_steam_id64, FancyPlayer},
+	database::{
+		crd::read::escape_like,
+		schemas::{account_id_to_steam_id64, FancyPlayer},
+	},
 	gokz_rs::prelude::SteamID,
 	log::debug,
 	sqlx::QueryBuilder,
@@ -44,7 +47,7 @@ pub(crate) async fn get(
 	} else {
 		query
 			.push(" WHERE s.name LIKE ")
-			.push_bind(format!("%{server_ident}%"));
+			.push_bind(format!("%{}%", escape_like(&server_ident)));
 	};
 
 	let result = query
@@ -82,5 +85,6 @@ pub(crate) async fn get(
 	Ok(Json(ResponseBody {
 		result,
 		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
 	}))
 }
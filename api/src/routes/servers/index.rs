@@ -1,13 +1,16 @@
 use {
 	super::{Server, ServerQuery},
-	crate::{GlobalState, Response, ResponseBody},
+	crate::{cursor, openapi::schema, GlobalState, Response, ResponseBody},
 	axum::{
 		extract::{Query, State},
 		Json,
 	},
 	color_eyre::eyre::eyre,
-	database::schemas::{
-		account_id_to_steam_id64, steam_id64_to_account_id, steam_id_to_account_id, FancyPlayer,
+	database::{
+		crd::read::escape_like,
+		schemas::{
+			account_id_to_steam_id64, steam_id64_to_account_id, steam_id_to_account_id, FancyPlayer,
+		},
 	},
 	gokz_rs::prelude::*,
 	log::debug,
@@ -22,8 +25,17 @@ pub(crate) struct Params {
 	owned_by: Option<String>,
 	approved_by: Option<String>,
 	limit: Option<u32>,
+	cursor: Option<String>,
 }
 
+schema!(Params {
+	name,
+	owned_by,
+	approved_by,
+	limit,
+	cursor
+});
+
 pub(crate) async fn get(
 	Query(params): Query<Params>,
 	State(GlobalState { pool }): State<GlobalState>,
@@ -54,7 +66,7 @@ pub(crate) async fn get(
 	if let Some(name) = params.name {
 		query
 			.push(" WHERE s.name LIKE ")
-			.push_bind(format!("%{name}%"));
+			.push_bind(format!("%{}%", escape_like(&name)));
 		multiple_filers = true;
 	}
 
@@ -66,7 +78,7 @@ pub(crate) async fn get(
 			PlayerIdentifier::Name(name) => {
 				query
 					.push(" o.name LIKE ")
-					.push_bind(format!("%{name}%"));
+					.push_bind(format!("%{}%", escape_like(&name)));
 			}
 			PlayerIdentifier::SteamID(steam_id) => {
 				let account_id = steam_id_to_account_id(&steam_id.to_string())
@@ -93,7 +105,7 @@ pub(crate) async fn get(
 			PlayerIdentifier::Name(name) => {
 				query
 					.push(" a.name LIKE ")
-					.push_bind(format!("%{name}%"));
+					.push_bind(format!("%{}%", escape_like(&name)));
 			}
 			PlayerIdentifier::SteamID(steam_id) => {
 				let account_id = steam_id_to_account_id(&steam_id.to_string())
@@ -109,13 +121,29 @@ pub(crate) async fn get(
 					.push_bind(account_id);
 			}
 		};
+		multiple_filers = true;
 	}
 
-	query.push(" LIMIT ").push_bind(
-		params
-			.limit
-			.map_or(1500, |limit| limit.min(1500)),
-	);
+	if let Some(cursor) = params.cursor {
+		let [id] = cursor::decode::<1>(&cursor)?;
+		let id = id
+			.parse::<u16>()
+			.map_err(|_| cursor::invalid(&cursor))?;
+
+		query
+			.push(if multiple_filers { " AND " } else { " WHERE " })
+			.push(" s.id > ")
+			.push_bind(id);
+	}
+
+	let limit = params
+		.limit
+		.map_or(1500, |limit| limit.min(1500));
+
+	query
+		.push(" ORDER BY s.id ")
+		.push(" LIMIT ")
+		.push_bind(limit);
 
 	let result = query
 		.build_query_as::<ServerQuery>()
@@ -126,6 +154,11 @@ pub(crate) async fn get(
 		return Err(sqlx::Error::RowNotFound.into());
 	}
 
+	let next_cursor = result
+		.last()
+		.filter(|_| result.len() as u32 == limit)
+		.map(|server_query| cursor::encode(&[&server_query.id.to_string()]));
+
 	let result = result
 		.into_iter()
 		.map(|server_query| {
@@ -158,5 +191,6 @@ pub(crate) async fn get(
 	Ok(Json(ResponseBody {
 		result,
 		took: (Instant::now() - start).as_nanos(),
+		next_cursor,
 	}))
 }
@@ -1,4 +1,9 @@
-use {database::schemas::FancyPlayer, serde::Serialize, sqlx::FromRow};
+use {
+	crate::openapi::{schema, Spec, PLAYER_IDENTIFIER, SERVER_IDENTIFIER},
+	database::schemas::FancyPlayer,
+	serde::Serialize,
+	sqlx::FromRow,
+};
 
 mod index;
 pub(crate) use index::get as index;
@@ -6,6 +11,9 @@ pub(crate) use index::get as index;
 mod ident;
 pub(crate) use ident::get as ident;
 
+mod stats;
+pub(crate) use stats::get as stats;
+
 #[derive(Debug, FromRow)]
 struct ServerQuery {
 	pub id: u16,
@@ -25,3 +33,27 @@ pub struct Server {
 	pub owned_by: FancyPlayer,
 	pub approved_by: FancyPlayer,
 }
+
+schema!(Server {
+	id,
+	name,
+	owned_by,
+	approved_by
+});
+
+pub(crate) fn docs(spec: &mut Spec) {
+	spec.get::<(), Server>("/api/servers/{ident}", "Get a server by an identifier.")
+		.path_param::<String>("ident", SERVER_IDENTIFIER);
+	spec.get::<index::Params, Vec<Server>>("/api/servers", "Get servers.")
+		.query_param("name", "Part of the server's name.")
+		.query_param("owned_by", PLAYER_IDENTIFIER)
+		.query_param("approved_by", PLAYER_IDENTIFIER);
+	spec.get::<stats::Params, stats::ServerStats>(
+		"/api/servers/{ident}/stats",
+		"Get usage statistics of a server.",
+	)
+	.path_param::<String>("ident", SERVER_IDENTIFIER)
+	.query_param("days", "How many days of daily activity to include. Defaults to 30.")
+	.query_param("weeks", "How many weeks of weekly activity to include. Defaults to 12.")
+	.query_param("limit", "How many of the most played maps to include. Defaults to 10.");
+}
@@ -0,0 +1,256 @@
+use {
+	crate::{openapi::schema, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Path, Query, State},
+		Json,
+	},
+	database::crd::read::get_server,
+	log::debug,
+	serde::{Deserialize, Serialize},
+	sqlx::FromRow,
+	std::time::Instant,
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	days: Option<u32>,
+	weeks: Option<u32>,
+	limit: Option<u32>,
+}
+
+schema!(Params { days, weeks, limit });
+
+#[derive(Debug, FromRow)]
+struct TotalsQuery {
+	total_records: i64,
+	unique_players: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct MapActivityQuery {
+	map_id: u16,
+	map_name: String,
+	records: i64,
+	unique_players: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct ActivityQuery {
+	period: String,
+	records: i64,
+	unique_players: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServerStats {
+	id: u16,
+	name: String,
+	total_records: u32,
+	unique_players: u32,
+	world_records: u32,
+	most_played_maps: Vec<MapActivity>,
+	daily_activity: Vec<Activity>,
+	weekly_activity: Vec<Activity>,
+}
+
+schema!(ServerStats {
+	id,
+	name,
+	total_records,
+	unique_players,
+	world_records,
+	most_played_maps,
+	daily_activity,
+	weekly_activity
+});
+
+#[derive(Debug, Serialize)]
+pub struct MapActivity {
+	map_id: u16,
+	map_name: String,
+	records: u32,
+	unique_players: u32,
+}
+
+schema!(MapActivity {
+	map_id,
+	map_name,
+	records,
+	unique_players
+});
+
+#[derive(Debug, Serialize)]
+pub struct Activity {
+	period: String,
+	records: u32,
+	unique_players: u32,
+}
+
+schema!(Activity {
+	period,
+	records,
+	unique_players
+});
+
+impl From<ActivityQuery> for Activity {
+	fn from(activity: ActivityQuery) -> Self {
+		Self {
+			period: activity.period,
+			records: activity.records as u32,
+			unique_players: activity.unique_players as u32,
+		}
+	}
+}
+
+pub(crate) async fn get(
+	Path(server_ident): Path<String>,
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<ServerStats> {
+	let start = Instant::now();
+	debug!("[servers::stats::get]");
+	debug!("> `server_ident`: {server_ident:#?}");
+	debug!("> `params`: {params:#?}");
+
+	let server = get_server(server_ident, &pool).await?;
+
+	let totals = sqlx::query_as::<_, TotalsQuery>(
+		r#"
+		SELECT
+		  COUNT(*) AS total_records,
+		  COUNT(DISTINCT player_id) AS unique_players
+		FROM records
+		WHERE server_id = ?
+		"#,
+	)
+	.bind(server.id)
+	.fetch_one(&pool)
+	.await?;
+
+	let (world_records,) = sqlx::query_as::<_, (i64,)>(
+		r#"
+		SELECT COUNT(DISTINCT r.mode_id, r.course_id, wr.has_teleports)
+		FROM (
+		  SELECT
+		    r_inner.mode_id,
+		    r_inner.course_id,
+		    CASE WHEN r_inner.teleports = 0 THEN 0 ELSE 1 END AS has_teleports,
+		    MIN(r_inner.time) AS time
+		  FROM records AS r_inner
+		  GROUP BY r_inner.mode_id, r_inner.course_id, has_teleports
+		) AS wr
+		JOIN records AS r
+		  ON r.mode_id = wr.mode_id
+		  AND r.course_id = wr.course_id
+		  AND r.time = wr.time
+		  AND CASE WHEN r.teleports = 0 THEN 0 ELSE 1 END = wr.has_teleports
+		WHERE r.server_id = ?
+		"#,
+	)
+	.bind(server.id)
+	.fetch_one(&pool)
+	.await?;
+
+	let limit = params
+		.limit
+		.map_or(10, |limit| limit.min(100));
+
+	let most_played_maps = sqlx::query_as::<_, MapActivityQuery>(
+		r#"
+		SELECT
+		  map.id AS map_id,
+		  map.name AS map_name,
+		  COUNT(*) AS records,
+		  COUNT(DISTINCT r.player_id) AS unique_players
+		FROM records AS r
+		JOIN courses AS c ON c.id = r.course_id
+		JOIN maps AS map ON map.id = c.map_id
+		WHERE r.server_id = ?
+		GROUP BY map.id
+		ORDER BY records DESC, map.name ASC
+		LIMIT ?
+		"#,
+	)
+	.bind(server.id)
+	.bind(limit)
+	.fetch_all(&pool)
+	.await?
+	.into_iter()
+	.map(|map| MapActivity {
+		map_id: map.map_id,
+		map_name: map.map_name,
+		records: map.records as u32,
+		unique_players: map.unique_players as u32,
+	})
+	.collect();
+
+	let days = params
+		.days
+		.map_or(30, |days| days.min(365));
+
+	let daily_activity = sqlx::query_as::<_, ActivityQuery>(
+		r#"
+		SELECT
+		  DATE_FORMAT(created_on, "%Y-%m-%d") AS period,
+		  COUNT(*) AS records,
+		  COUNT(DISTINCT player_id) AS unique_players
+		FROM records
+		WHERE server_id = ?
+		AND created_on >= CURRENT_DATE - INTERVAL ? DAY
+		GROUP BY period
+		ORDER BY period DESC
+		"#,
+	)
+	.bind(server.id)
+	.bind(days)
+	.fetch_all(&pool)
+	.await?
+	.into_iter()
+	.map(Activity::from)
+	.collect();
+
+	let weeks = params
+		.weeks
+		.map_or(12, |weeks| weeks.min(104));
+
+	// ISO 8601 weeks, e.g. `2023-W07`
+	let weekly_activity = sqlx::query_as::<_, ActivityQuery>(
+		r#"
+		SELECT
+		  DATE_FORMAT(created_on, "%x-W%v") AS period,
+		  COUNT(*) AS records,
+		  COUNT(DISTINCT player_id) AS unique_players
+		FROM records
+		WHERE server_id = ?
+		AND created_on >= CURRENT_DATE - INTERVAL ? WEEK
+		GROUP BY period
+		ORDER BY period DESC
+		"#,
+	)
+	.bind(server.id)
+	.bind(weeks)
+	.fetch_all(&pool)
+	.await?
+	.into_iter()
+	.map(Activity::from)
+	.collect();
+
+	let result = ServerStats {
+		id: server.id,
+		name: server.name,
+		total_records: totals.total_records as u32,
+		unique_players: totals.unique_players as u32,
+		world_records: world_records as u32,
+		most_played_maps,
+		daily_activity,
+		weekly_activity,
+	};
+
+	debug!("> {result:#?}");
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
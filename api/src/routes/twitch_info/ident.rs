@@ -0,0 +1,41 @@
+use {
+	super::{BestRecords, Streamer, StreamerQuery, STREAMER_QUERY},
+	crate::{GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Path, State},
+		Json,
+	},
+	log::debug,
+	sqlx::QueryBuilder,
+	std::time::Instant,
+};
+
+pub(crate) async fn get(
+	Path(channel_name): Path<String>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Streamer> {
+	let start = Instant::now();
+	debug!("[twitch_info::ident::get]");
+	debug!("> `channel_name`: {channel_name:#?}");
+
+	let mut query = QueryBuilder::new(STREAMER_QUERY);
+	query
+		.push(" WHERE channel_name = ")
+		.push_bind(channel_name.to_lowercase());
+
+	let streamer = query
+		.build_query_as::<StreamerQuery>()
+		.fetch_one(&pool)
+		.await?;
+
+	let best_records = BestRecords::fetch(std::slice::from_ref(&streamer), &pool).await?;
+	let result = streamer.into_streamer(&best_records);
+
+	debug!("> {result:#?}");
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
@@ -0,0 +1,67 @@
+use {
+	super::{BestRecords, Streamer, StreamerQuery, STREAMER_QUERY},
+	crate::{openapi::schema, GlobalState, Response, ResponseBody},
+	axum::{
+		extract::{Query, State},
+		Json,
+	},
+	log::debug,
+	serde::Deserialize,
+	sqlx::QueryBuilder,
+	std::time::Instant,
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Params {
+	map: Option<String>,
+	limit: Option<u32>,
+}
+
+schema!(Params { map, limit });
+
+pub(crate) async fn get(
+	Query(params): Query<Params>,
+	State(GlobalState { pool }): State<GlobalState>,
+) -> Response<Vec<Streamer>> {
+	let start = Instant::now();
+	debug!("[twitch_info::index::get]");
+	debug!("> `params`: {params:#?}");
+
+	let mut query = QueryBuilder::new(STREAMER_QUERY);
+	query.push(" WHERE channel_name IS NOT NULL ");
+
+	if let Some(map_name) = params.map {
+		query
+			.push(" AND map_name = ")
+			.push_bind(map_name);
+	}
+
+	let limit = params
+		.limit
+		.map_or(50, |limit| limit.min(100));
+
+	query
+		.push(" ORDER BY channel_name ASC LIMIT ")
+		.push_bind(limit);
+
+	let streamers = query
+		.build_query_as::<StreamerQuery>()
+		.fetch_all(&pool)
+		.await?;
+
+	if streamers.is_empty() {
+		return Err(sqlx::Error::RowNotFound.into());
+	}
+
+	let best_records = BestRecords::fetch(&streamers, &pool).await?;
+	let result = streamers
+		.into_iter()
+		.map(|streamer| streamer.into_streamer(&best_records))
+		.collect();
+
+	Ok(Json(ResponseBody {
+		result,
+		took: (Instant::now() - start).as_nanos(),
+		next_cursor: None,
+	}))
+}
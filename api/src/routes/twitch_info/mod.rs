@@ -0,0 +1,361 @@
+use {
+	crate::{
+		openapi::{schema, Spec, MAP_IDENTIFIER},
+		routes::{
+			maps::Course,
+			records::{Record, RecordQuery},
+		},
+		Error,
+	},
+	database::schemas::{account_id_to_steam_id64, steam_id_to_account_id, FancyPlayer},
+	gokz_rs::prelude::{Mode, SteamID},
+	serde::Serialize,
+	sqlx::{FromRow, MySql, Pool, QueryBuilder},
+	std::collections::{HashMap, HashSet},
+};
+
+mod update;
+pub(crate) use update::post as update;
+
+mod index;
+pub(crate) use index::get as index;
+
+mod ident;
+pub(crate) use ident::get as ident;
+
+#[derive(Debug, FromRow)]
+pub(crate) struct StreamerQuery {
+	pub(crate) channel_name: String,
+	pub(crate) player_name: Option<String>,
+	pub(crate) steam_id: Option<String>,
+	pub(crate) mode: Option<String>,
+	pub(crate) map_name: Option<String>,
+	pub(crate) map_tier: Option<u8>,
+}
+
+pub(crate) const STREAMER_QUERY: &str = r#"
+	SELECT
+	  channel_name,
+	  player_name,
+	  steam_id,
+	  mode,
+	  map_name,
+	  map_tier
+	FROM streamers
+"#;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Streamer {
+	pub(crate) channel_name: String,
+	pub(crate) player_name: Option<String>,
+	pub(crate) steam_id: Option<String>,
+	pub(crate) mode: Option<String>,
+	pub(crate) map_name: Option<String>,
+	pub(crate) map_tier: Option<u8>,
+	pub(crate) personal_best: BestTimes,
+	pub(crate) world_record: BestTimes,
+}
+
+schema!(Streamer {
+	channel_name,
+	player_name,
+	steam_id,
+	mode,
+	map_name,
+	map_tier,
+	personal_best,
+	world_record
+});
+
+/// Best records on the main course of the map the streamer is currently playing.
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct BestTimes {
+	pub(crate) tp: Option<Record>,
+	pub(crate) pro: Option<Record>,
+}
+
+schema!(BestTimes { tp, pro });
+
+impl StreamerQuery {
+	fn mode_id(&self) -> Option<u8> {
+		self.mode
+			.as_ref()
+			.and_then(|mode| mode.parse::<Mode>().ok())
+			.map(|mode| mode as u8)
+	}
+
+	fn player_id(&self) -> Option<u32> {
+		self.steam_id
+			.as_deref()
+			.and_then(steam_id_to_account_id)
+	}
+
+	pub(crate) fn into_streamer(self, best_records: &BestRecords) -> Streamer {
+		let mut personal_best = BestTimes::default();
+		let mut world_record = BestTimes::default();
+
+		if let (Some(map_name), Some(mode_id)) = (&self.map_name, self.mode_id()) {
+			let map_name = map_name.to_lowercase();
+
+			world_record.tp = best_records.get(&map_name, mode_id, true, None);
+			world_record.pro = best_records.get(&map_name, mode_id, false, None);
+
+			if let Some(player_id) = self.player_id() {
+				personal_best.tp = best_records.get(&map_name, mode_id, true, Some(player_id));
+				personal_best.pro = best_records.get(&map_name, mode_id, false, Some(player_id));
+			}
+		}
+
+		Streamer {
+			channel_name: self.channel_name,
+			player_name: self.player_name,
+			steam_id: self.steam_id,
+			mode: self.mode,
+			map_name: self.map_name,
+			map_tier: self.map_tier,
+			personal_best,
+			world_record,
+		}
+	}
+}
+
+#[derive(Debug, FromRow)]
+struct BestRecordQuery {
+	mode_id: u8,
+	#[sqlx(flatten)]
+	record: RecordQuery,
+}
+
+/// `(map_name, mode_id, has_teleports, player_id)`, `player_id` is `None` for world records.
+type BestRecordKey = (String, u8, bool, Option<u32>);
+
+/// World records and personal bests on the main courses of the maps a list of streamers is
+/// currently playing. These are fetched up front, so the amount of queries doesn't depend on the
+/// amount of streamers.
+#[derive(Debug, Default)]
+pub(crate) struct BestRecords(HashMap<BestRecordKey, Record>);
+
+impl BestRecords {
+	pub(crate) async fn fetch(
+		streamers: &[StreamerQuery],
+		pool: &Pool<MySql>,
+	) -> Result<Self, Error> {
+		let mut best_records = Self::default();
+
+		let courses = streamers
+			.iter()
+			.filter_map(|streamer| {
+				Some((
+					streamer
+						.map_name
+						.as_ref()?
+						.to_lowercase(),
+					streamer.mode_id()?,
+				))
+			})
+			.collect::<HashSet<_>>();
+
+		if courses.is_empty() {
+			return Ok(best_records);
+		}
+
+		let players = streamers
+			.iter()
+			.filter_map(|streamer| {
+				Some((
+					streamer
+						.map_name
+						.as_ref()?
+						.to_lowercase(),
+					streamer.mode_id()?,
+					streamer.player_id()?,
+				))
+			})
+			.collect::<HashSet<_>>();
+
+		for record in best_record_query(&courses, None)
+			.build_query_as::<BestRecordQuery>()
+			.fetch_all(pool)
+			.await?
+		{
+			best_records.insert(record, false);
+		}
+
+		if !players.is_empty() {
+			for record in best_record_query(&courses, Some(&players))
+				.build_query_as::<BestRecordQuery>()
+				.fetch_all(pool)
+				.await?
+			{
+				best_records.insert(record, true);
+			}
+		}
+
+		Ok(best_records)
+	}
+
+	fn insert(&mut self, BestRecordQuery { mode_id, record }: BestRecordQuery, is_pb: bool) {
+		let key = (
+			record.map_name.to_lowercase(),
+			mode_id,
+			record.teleports > 0,
+			is_pb.then_some(record.player_id),
+		);
+
+		let steam_id64 = account_id_to_steam_id64(record.player_id);
+		let steam_id = SteamID::from(steam_id64);
+
+		self.0.insert(
+			key,
+			Record {
+				id: record.id,
+				map_name: record.map_name,
+				course: Course {
+					id: record.course_id,
+					stage: record.stage,
+					kzt: record.kzt,
+					kzt_difficulty: record.kzt_difficulty,
+					skz: record.skz,
+					skz_difficulty: record.skz_difficulty,
+					vnl: record.vnl,
+					vnl_difficulty: record.vnl_difficulty,
+				},
+				mode: record.mode,
+				player: FancyPlayer {
+					id: record.player_id,
+					name: record.player_name,
+					steam_id: steam_id.to_string(),
+					steam_id64: steam_id64.to_string(),
+					is_banned: record.player_is_banned,
+				},
+				server_name: record.server_name,
+				time: record.time,
+				teleports: record.teleports,
+				created_on: record.created_on,
+			},
+		);
+	}
+
+	fn get(
+		&self,
+		map_name: &str,
+		mode_id: u8,
+		has_teleports: bool,
+		player_id: Option<u32>,
+	) -> Option<Record> {
+		self.0
+			.get(&(map_name.to_owned(), mode_id, has_teleports, player_id))
+			.cloned()
+	}
+}
+
+/// Fastest records on the main course of every `(map_name, mode_id)` pair in `courses`. If
+/// `players` is given, this returns the personal best of every `(map_name, mode_id, player_id)`
+/// in it instead.
+fn best_record_query<'a>(
+	courses: &'a HashSet<(String, u8)>,
+	players: Option<&'a HashSet<(String, u8, u32)>>,
+) -> QueryBuilder<'a, MySql> {
+	let per_player = if players.is_some() { ", r_inner.player_id" } else { "" };
+
+	let mut query = QueryBuilder::new(format!(
+		r#"
+		SELECT
+		  r.mode_id AS mode_id,
+		  r.id AS id,
+		  map.id AS map_id,
+		  map.name AS map_name,
+		  c.id AS course_id,
+		  c.stage AS stage,
+		  c.kzt AS kzt,
+		  c.kzt_difficulty AS kzt_difficulty,
+		  c.skz AS skz,
+		  c.skz_difficulty AS skz_difficulty,
+		  c.vnl AS vnl,
+		  c.vnl_difficulty AS vnl_difficulty,
+		  mode.name AS mode,
+		  p.id AS player_id,
+		  p.name AS player_name,
+		  p.is_banned AS player_is_banned,
+		  s.name AS server_name,
+		  r.time AS time,
+		  r.teleports AS teleports,
+		  r.created_on AS created_on
+		FROM (
+		  SELECT MIN(r_best.id) AS id
+		  FROM (
+		    SELECT
+		      r_inner.course_id,
+		      r_inner.mode_id,
+		      r_inner.teleports > 0 AS has_teleports,
+		      MIN(r_inner.time) AS time{per_player}
+		    FROM records AS r_inner
+		    JOIN courses AS c ON c.id = r_inner.course_id AND c.stage = 0
+		    JOIN maps AS map ON map.id = c.map_id
+		    JOIN players AS p ON p.id = r_inner.player_id AND p.is_banned = 0
+		    WHERE
+		"#
+	));
+
+	match players {
+		None => {
+			query
+				.push(" (LOWER(map.name), r_inner.mode_id) IN ")
+				.push_tuples(courses, |mut query, (map_name, mode_id)| {
+					query
+						.push_bind(map_name)
+						.push_bind(mode_id);
+				});
+		}
+		Some(players) => {
+			query
+				.push(" (LOWER(map.name), r_inner.mode_id, r_inner.player_id) IN ")
+				.push_tuples(players, |mut query, (map_name, mode_id, player_id)| {
+					query
+						.push_bind(map_name)
+						.push_bind(mode_id)
+						.push_bind(player_id);
+				});
+		}
+	}
+
+	query.push(format!(
+		r#"
+		    GROUP BY r_inner.course_id, r_inner.mode_id, has_teleports{per_player}
+		  ) AS best
+		  JOIN records AS r_best
+		    ON r_best.course_id = best.course_id
+		    AND r_best.mode_id = best.mode_id
+		    AND (r_best.teleports > 0) = best.has_teleports
+		    AND r_best.time = best.time
+		    {player}
+		  JOIN players AS p_best ON p_best.id = r_best.player_id AND p_best.is_banned = 0
+		  GROUP BY best.course_id, best.mode_id, best.has_teleports{best_player}
+		) AS best_ids
+		JOIN records AS r ON r.id = best_ids.id
+		JOIN courses AS c ON c.id = r.course_id
+		JOIN maps AS map ON map.id = c.map_id
+		JOIN modes AS mode ON mode.id = r.mode_id
+		JOIN players AS p ON p.id = r.player_id
+		JOIN servers AS s ON s.id = r.server_id
+		"#,
+		player = if players.is_some() { "AND r_best.player_id = best.player_id" } else { "" },
+		best_player = if players.is_some() { ", best.player_id" } else { "" },
+	));
+
+	query
+}
+
+pub(crate) fn docs(spec: &mut Spec) {
+	spec.get::<(), Streamer>(
+		"/api/twitch_info/{channel}",
+		"Get what a Twitch streamer is currently playing, along with their personal best and the world record on the main course of that map.",
+	)
+	.path_param::<String>("channel", "The streamer's Twitch channel name.");
+	spec.get::<index::Params, Vec<Streamer>>("/api/twitch_info", "Get all Twitch streamers.")
+		.query_param("map", MAP_IDENTIFIER);
+	spec.post::<update::Info>(
+		"/api/twitch_info",
+		"Update what a streamer is currently playing. Requires an API key with the `twitch` scope.",
+	);
+}
@@ -1,13 +1,13 @@
 use {
-	crate::GlobalState,
+	crate::{auth::Caller, openapi::schema, GlobalState},
 	axum::{
 		extract::{Json, State},
-		http::{HeaderMap, StatusCode},
+		http::StatusCode,
+		Extension,
 	},
 	gokz_rs::prelude::{Mode, SteamID},
 	log::{debug, error},
 	serde::{Deserialize, Serialize},
-	sqlx::FromRow,
 };
 
 /// Information payload sent by the GSI desktop client for SchnoseBot (Twitch).
@@ -19,6 +19,13 @@ pub struct Info {
 	pub map: Option<MapInfo>,
 }
 
+schema!(Info {
+	player_name,
+	steam_id,
+	mode,
+	map
+});
+
 /// Information about the current map being played.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MapInfo {
@@ -26,35 +33,26 @@ pub struct MapInfo {
 	pub tier: Option<u8>,
 }
 
+schema!(MapInfo { name, tier });
+
 pub(crate) async fn post(
-	headers: HeaderMap,
+	Extension(caller): Extension<Caller>,
 	State(GlobalState { pool }): State<GlobalState>,
 	Json(info): Json<Info>,
 ) -> StatusCode {
-	debug!("Headers: {headers:?}");
 	debug!("Body: {info:?}");
 
-	let Some(api_key) = headers.get("x-schnose-auth-key") else {
-		debug!("missing API key header");
-		return StatusCode::BAD_REQUEST
-	};
-
-	let Ok(api_key) = api_key.to_str() else {
-		debug!("API key is not a string");
-		return StatusCode::BAD_REQUEST
+	let api_key = match caller {
+		Caller::Key(api_key) => api_key,
+		Caller::Anonymous(_) => {
+			debug!("missing API key header");
+			return StatusCode::UNAUTHORIZED;
+		}
 	};
 
-	let api_key = ApiKey(api_key.to_owned());
-
-	let Ok(api_keys) = sqlx::query_as::<_, ApiKey>("SELECT * FROM api_keys").fetch_all(&pool).await else {
-		debug!("failed to fetch API keys");
-		return StatusCode::INTERNAL_SERVER_ERROR
-	};
-
-	if !api_keys.contains(&api_key) {
-		debug!("invalid API key ({api_key:?})");
-		debug!("api keys: {api_keys:?}");
-		return StatusCode::UNAUTHORIZED;
+	if !api_key.has_scope("twitch") {
+		debug!("API key `{}` is missing the `twitch` scope", api_key.name);
+		return StatusCode::FORBIDDEN;
 	}
 
 	let (map_name, map_tier) = match &info.map {
@@ -84,7 +82,7 @@ pub(crate) async fn post(
 			.map(|mode| mode.api())),
 		map_name,
 		map_tier,
-		api_key.0
+		api_key.api_key
 	)
 	.execute(&pool)
 	.await
@@ -95,6 +93,3 @@ pub(crate) async fn post(
 
 	StatusCode::OK
 }
-
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, FromRow)]
-struct ApiKey(String);
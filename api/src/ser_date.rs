@@ -16,3 +16,16 @@ where
 	date.replace(' ', "T")
 		.serialize(serializer)
 }
+
+pub(crate) fn ser_opt_date<S>(
+	date: &Option<PrimitiveDateTime>,
+	serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	match date {
+		Some(date) => ser_date(date, serializer),
+		None => serializer.serialize_none(),
+	}
+}
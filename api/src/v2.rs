@@ -0,0 +1,212 @@
+//! `/api/v2` serves the same handlers as v1; only the shape of the responses differs.
+//!
+//! Successful responses are wrapped as `{ data, meta, request_id }`, errors look like
+//! `{ error: { code, message, expected, request_id } }` and use proper status codes. The
+//! [`envelope`] middleware rewrites v1 responses into that format, so handlers don't have to care
+//! which version they are serving.
+
+use {
+	crate::{Error, ResponseBody as V1ResponseBody},
+	axum::{
+		body::{boxed, Full},
+		http::{
+			header::{CONTENT_LENGTH, CONTENT_TYPE},
+			HeaderValue, Request, StatusCode,
+		},
+		middleware::Next,
+		response::Response,
+	},
+	serde::Serialize,
+	serde_json::Value,
+	std::{
+		sync::atomic::{AtomicU64, Ordering},
+		time::{SystemTime, UNIX_EPOCH},
+	},
+};
+
+pub(crate) const PREFIX: &str = "/api/v2";
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+#[derive(Debug, Serialize)]
+struct ResponseBody {
+	data: Value,
+	meta: Meta,
+	request_id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Meta {
+	/// How long the request took to process, in milliseconds.
+	took_ms: f64,
+	/// How many entries `data` contains, if it is a list.
+	count: Option<usize>,
+	/// The `limit` that was requested, if any.
+	limit: Option<u32>,
+	/// Pass this as `cursor` to get the next page.
+	next_cursor: Option<String>,
+	has_more: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+	error: ErrorObject,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorObject {
+	code: &'static str,
+	message: String,
+	expected: Option<String>,
+	request_id: String,
+}
+
+pub(crate) async fn envelope<B>(request: Request<B>, next: Next<B>) -> Response {
+	if !request.uri().path().starts_with(PREFIX) {
+		return next.run(request).await;
+	}
+
+	let request_id = request
+		.headers()
+		.get(REQUEST_ID_HEADER)
+		.and_then(|request_id| request_id.to_str().ok())
+		.map(ToOwned::to_owned)
+		.unwrap_or_else(new_request_id);
+
+	let limit = request.uri().query().and_then(|query| {
+		query.split('&').find_map(|param| {
+			param
+				.strip_prefix("limit=")?
+				.parse()
+				.ok()
+		})
+	});
+
+	let response = next.run(request).await;
+	let (mut parts, body) = response.into_parts();
+
+	let is_json = parts
+		.headers
+		.get(CONTENT_TYPE)
+		.and_then(|content_type| content_type.to_str().ok())
+		.is_some_and(|content_type| content_type.starts_with("application/json"));
+
+	let new_body = if let Some(error) = parts.extensions.remove::<Error>() {
+		parts.status = error.status();
+		error_body(
+			error.code(),
+			error.to_string(),
+			error.expected().map(ToOwned::to_owned),
+			&request_id,
+		)
+	} else if parts.status.is_client_error() || parts.status.is_server_error() {
+		// rejections from axum and errors from the auth middleware; their bodies are short strings
+		let bytes = hyper::body::to_bytes(body)
+			.await
+			.unwrap_or_default();
+		let message = match serde_json::from_slice::<String>(&bytes) {
+			Ok(message) => message,
+			Err(_) if bytes.is_empty() => parts
+				.status
+				.canonical_reason()
+				.unwrap_or_default()
+				.to_owned(),
+			Err(_) => String::from_utf8_lossy(&bytes).into_owned(),
+		};
+
+		let code = match parts.status {
+			StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => {
+				parts.status = StatusCode::UNPROCESSABLE_ENTITY;
+				"invalid_request"
+			}
+			StatusCode::UNAUTHORIZED => "unauthorized",
+			StatusCode::FORBIDDEN => "forbidden",
+			StatusCode::NOT_FOUND => "not_found",
+			StatusCode::METHOD_NOT_ALLOWED => "method_not_allowed",
+			StatusCode::TOO_MANY_REQUESTS => "rate_limited",
+			_ => "internal_error",
+		};
+
+		error_body(code, message, None, &request_id)
+	} else if is_json {
+		let bytes = hyper::body::to_bytes(body)
+			.await
+			.unwrap_or_default();
+
+		match serde_json::from_slice::<V1ResponseBody<Value>>(&bytes) {
+			Ok(V1ResponseBody {
+				result,
+				took,
+				next_cursor,
+			}) => {
+				let body = ResponseBody {
+					meta: Meta {
+						took_ms: took as f64 / 1_000_000.0,
+						count: result.as_array().map(Vec::len),
+						limit,
+						has_more: next_cursor.is_some(),
+						next_cursor,
+					},
+					data: result,
+					request_id: request_id.clone(),
+				};
+
+				serde_json::to_vec(&body).expect("`Value`s always serialize")
+			}
+			// not an API response (e.g. `/api/v2/openapi.json`), pass it through untouched
+			Err(_) => bytes.to_vec(),
+		}
+	} else {
+		// HTML, server-sent events, ...
+		let mut response = Response::from_parts(parts, boxed(body));
+		set_request_id(&mut response, &request_id);
+		return response;
+	};
+
+	parts.headers.remove(CONTENT_LENGTH);
+	parts
+		.headers
+		.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+	let mut response = Response::from_parts(parts, boxed(Full::from(new_body)));
+	set_request_id(&mut response, &request_id);
+	response
+}
+
+fn error_body(
+	code: &'static str,
+	message: String,
+	expected: Option<String>,
+	request_id: &str,
+) -> Vec<u8> {
+	let body = ErrorBody {
+		error: ErrorObject {
+			code,
+			message,
+			expected,
+			request_id: request_id.to_owned(),
+		},
+	};
+
+	serde_json::to_vec(&body).expect("error bodies always serialize")
+}
+
+fn set_request_id(response: &mut Response, request_id: &str) {
+	if let Ok(request_id) = HeaderValue::from_str(request_id) {
+		response
+			.headers_mut()
+			.insert(REQUEST_ID_HEADER, request_id);
+	}
+}
+
+/// Unique enough to find a request in the logs: the current time plus a counter.
+fn new_request_id() -> String {
+	static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+	let now = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.unwrap_or_default()
+		.as_millis();
+	let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+	format!("{now:x}-{count:x}")
+}
@@ -6,6 +6,7 @@ use {
 	gokz_rs::{maps::Map, modes::APIMode, players::Player, servers::Server},
 	log::info,
 	migrations::{
+		runner,
 		schemas::{
 			self,
 			mappers::{InputKind, KZGOInput as MapperInput, ZeroInput},
@@ -50,11 +51,36 @@ async fn main() -> Eyre<()> {
 	let gokz_client = gokz_rs::Client::new();
 
 	match args.action {
-		SqlAction::Up => migrations::up(&pool).await?,
-		SqlAction::Down => migrations::down(&pool).await?,
-		SqlAction::Redo => {
-			migrations::up(&pool).await?;
-			migrations::down(&pool).await?;
+		SqlAction::Status => {
+			let migrations = runner::migrations(&args.migrations_dir)?;
+			runner::status(&migrations, &pool).await?;
+		}
+		SqlAction::Up { to } => {
+			let migrations = runner::migrations(&args.migrations_dir)?;
+			let count = runner::up(&migrations, to.as_deref(), &pool).await?;
+			info!("Applied {count} migrations.");
+		}
+		SqlAction::Down { steps } => {
+			let migrations = runner::migrations(&args.migrations_dir)?;
+			let count = runner::down(&migrations, steps, &pool).await?;
+			info!("Rolled back {count} migrations.");
+		}
+		SqlAction::Baseline { to } => {
+			let migrations = runner::migrations(&args.migrations_dir)?;
+			let count = runner::baseline(&migrations, &to, &pool).await?;
+			info!("Marked {count} migrations as applied.");
+		}
+		SqlAction::Points => {
+			let count = schemas::points::recalculate(&pool).await?;
+			info!("Recalculated points for {count} leaderboards.");
+		}
+		SqlAction::RenameMap { map_id, name } => {
+			let count = database::crd::update::rename_map(map_id, &name, &pool).await?;
+			info!("Updated {count} rows in `maps`.");
+		}
+		SqlAction::Ban { player_id, unban } => {
+			let count = database::crd::update::set_ban(player_id, !unban, &pool).await?;
+			info!("Updated {count} rows in `players`.");
 		}
 		SqlAction::Insert { schema, data } => match schema {
 			Schema::Players => {
@@ -228,6 +254,11 @@ struct Args {
 	#[clap(default_value = "./config.toml")]
 	config_path: PathBuf,
 
+	/// Directory containing the `.sql` migrations.
+	#[arg(long)]
+	#[clap(default_value = "./migrations")]
+	migrations_dir: PathBuf,
+
 	/// Print debug output.
 	#[arg(long)]
 	debug: bool,
@@ -1,9 +1,4 @@
-mod up;
-pub use up::up;
-
-mod down;
-pub use down::down;
-
+pub mod runner;
 pub mod schemas;
 pub mod util;
 
@@ -11,10 +6,44 @@ use clap::{Subcommand, ValueEnum};
 
 #[derive(Debug, Clone, Subcommand)]
 pub enum SqlAction {
-	Up,
-	Down,
-	Redo,
-	Insert { schema: Schema, data: String },
+	/// Show which migrations have been applied.
+	Status,
+	/// Apply pending migrations.
+	Up {
+		/// Stop after this migration.
+		#[arg(long)]
+		to: Option<String>,
+	},
+	/// Roll back the most recently applied migrations.
+	Down {
+		#[arg(long)]
+		#[clap(default_value = "1")]
+		steps: usize,
+	},
+	/// Record migrations up to and including `to` as applied without running them, e.g. map
+	/// releases that have been applied by hand.
+	Baseline {
+		#[arg(long)]
+		to: String,
+	},
+	Insert {
+		schema: Schema,
+		data: String,
+	},
+	Points,
+	/// Rename a map, e.g. when a new version gets released under a different name.
+	RenameMap {
+		map_id: u16,
+		name: String,
+	},
+	/// Manually (un)ban a player by their account ID. Players with entries in `bans` get
+	/// overwritten by the next run of the ban scraper.
+	Ban {
+		player_id: u32,
+		#[arg(long)]
+		#[clap(default_value = "false")]
+		unban: bool,
+	},
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -0,0 +1,499 @@
+//! Versioned migrations.
+//!
+//! Every migration has a version, which also determines the order they are applied in. The table
+//! definitions in [`schemas`] come first, followed by the `.sql` files in the migrations directory,
+//! ordered by file name. Applied migrations are recorded in `schema_migrations` together with a
+//! checksum, so changing a migration after it has been applied is an error; write a new one
+//! instead.
+//!
+//! `.sql` files can contain a `-- migrate:down` line, everything after it is used to roll the
+//! migration back. Files without one can't be rolled back.
+
+use {
+	super::schemas,
+	chrono::NaiveDateTime,
+	color_eyre::{eyre::eyre, Result as Eyre},
+	log::{info, warn},
+	sha2::{Digest, Sha256},
+	sqlx::{Executor, FromRow, MySql, Pool},
+	std::{collections::HashMap, path::Path},
+};
+
+/// Tables in the order they have to be created in, as they were before versioned migrations
+/// existed. Their versions are derived from the position in this list, so this must not change;
+/// new tables get a `.sql` file in the migrations directory instead.
+const TABLES: [(&str, &str, &str); 8] = [
+	("players", schemas::players::up(), schemas::players::down()),
+	("modes", schemas::modes::up(), schemas::modes::down()),
+	("servers", schemas::servers::up(), schemas::servers::down()),
+	("maps", schemas::maps::up(), schemas::maps::down()),
+	("courses", schemas::courses::up(), schemas::courses::down()),
+	("records", schemas::records::up(), schemas::records::down()),
+	("streamers", schemas::streamers::up(), schemas::streamers::down()),
+	("api_keys", schemas::api_keys::up(), schemas::api_keys::down()),
+];
+
+const DOWN_MARKER: &str = "-- migrate:down";
+
+#[derive(Debug, Clone)]
+pub struct Migration {
+	pub version: String,
+	pub up: String,
+	pub down: Option<String>,
+	pub checksum: String,
+}
+
+impl Migration {
+	fn new(version: String, up: String, down: Option<String>) -> Self {
+		let checksum = format!("{:x}", Sha256::digest(up.as_bytes()));
+		Self {
+			version,
+			up,
+			down,
+			checksum,
+		}
+	}
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct AppliedMigration {
+	version: String,
+	checksum: String,
+	applied_on: NaiveDateTime,
+}
+
+/// Collects all known migrations, sorted by version.
+pub fn migrations(dir: &Path) -> Eyre<Vec<Migration>> {
+	let mut migrations = TABLES
+		.iter()
+		.enumerate()
+		.map(|(i, (table, up, down))| {
+			Migration::new(
+				format!("00000000_{i:02}_{table}"),
+				up.to_string(),
+				Some(down.to_string()),
+			)
+		})
+		.collect::<Vec<_>>();
+
+	for entry in std::fs::read_dir(dir)? {
+		let path = entry?.path();
+		if path
+			.extension()
+			.is_none_or(|extension| extension != "sql")
+		{
+			continue;
+		}
+
+		let version = path
+			.file_stem()
+			.and_then(|stem| stem.to_str())
+			.ok_or(eyre!("Invalid file name `{}`.", path.display()))?
+			.to_owned();
+
+		let sql = std::fs::read_to_string(&path)?;
+		let (up, down) = match sql.split_once(DOWN_MARKER) {
+			Some((up, down)) => (up.to_owned(), Some(down.to_owned())),
+			None => (sql, None),
+		};
+
+		migrations.push(Migration::new(version, up, down));
+	}
+
+	migrations.sort_by(|a, b| a.version.cmp(&b.version));
+
+	Ok(migrations)
+}
+
+pub async fn status(migrations: &[Migration], pool: &Pool<MySql>) -> Eyre<()> {
+	let applied = applied(pool).await?;
+
+	for migration in migrations {
+		let status = match applied.get(&migration.version) {
+			None => String::from("pending"),
+			Some(applied) if applied.checksum != migration.checksum => {
+				format!("applied on {}, CHANGED SINCE", applied.applied_on)
+			}
+			Some(applied) => format!("applied on {}", applied.applied_on),
+		};
+
+		println!("{} | {status}", migration.version);
+	}
+
+	for version in applied.keys() {
+		if !migrations
+			.iter()
+			.any(|migration| &migration.version == version)
+		{
+			println!("{version} | applied, but MISSING");
+		}
+	}
+
+	Ok(())
+}
+
+/// Applies all pending migrations up to and including `to`.
+pub async fn up(migrations: &[Migration], to: Option<&str>, pool: &Pool<MySql>) -> Eyre<usize> {
+	let applied = applied(pool).await?;
+	verify(migrations, &applied)?;
+	let pending = pending(migrations, &applied, to)?;
+
+	for migration in &pending {
+		info!("applying `{}`...", migration.version);
+
+		// MySQL commits DDL statements implicitly, so this only protects data migrations.
+		let mut transaction = pool.begin().await?;
+		transaction
+			.execute(migration.up.as_str())
+			.await?;
+		sqlx::query("INSERT INTO schema_migrations (version, checksum) VALUES (?, ?)")
+			.bind(&migration.version)
+			.bind(&migration.checksum)
+			.execute(&mut transaction)
+			.await?;
+		transaction.commit().await?;
+
+		info!("successfully applied `{}`.", migration.version);
+	}
+
+	Ok(pending.len())
+}
+
+/// Rolls back the `steps` most recently applied migrations.
+pub async fn down(migrations: &[Migration], steps: usize, pool: &Pool<MySql>) -> Eyre<usize> {
+	let applied = applied(pool).await?;
+	verify(migrations, &applied)?;
+
+	let rollback = migrations
+		.iter()
+		.rev()
+		.filter(|migration| applied.contains_key(&migration.version))
+		.take(steps)
+		.collect::<Vec<_>>();
+
+	// check everything first, so we don't stop halfway through
+	if let Some(migration) = rollback
+		.iter()
+		.find(|migration| migration.down.is_none())
+	{
+		return Err(eyre!("`{}` can't be rolled back.", migration.version));
+	}
+
+	for migration in &rollback {
+		let Some(down) = &migration.down else {
+			unreachable!("checked above");
+		};
+
+		warn!("rolling back `{}`...", migration.version);
+
+		let mut transaction = pool.begin().await?;
+		transaction
+			.execute(down.as_str())
+			.await?;
+		sqlx::query("DELETE FROM schema_migrations WHERE version = ?")
+			.bind(&migration.version)
+			.execute(&mut transaction)
+			.await?;
+		transaction.commit().await?;
+
+		info!("successfully rolled back `{}`.", migration.version);
+	}
+
+	Ok(rollback.len())
+}
+
+/// Records all migrations up to and including `to` as applied, without running them. Meant for
+/// databases that have been migrated by hand.
+pub async fn baseline(migrations: &[Migration], to: &str, pool: &Pool<MySql>) -> Eyre<usize> {
+	let applied = applied(pool).await?;
+	verify(migrations, &applied)?;
+
+	if !migrations
+		.iter()
+		.any(|migration| migration.version == to)
+	{
+		return Err(eyre!("Unknown migration `{to}`."));
+	}
+
+	let mut transaction = pool.begin().await?;
+	let mut count = 0;
+
+	for migration in migrations
+		.iter()
+		.filter(|migration| migration.version.as_str() <= to)
+		.filter(|migration| !applied.contains_key(&migration.version))
+	{
+		sqlx::query("INSERT INTO schema_migrations (version, checksum) VALUES (?, ?)")
+			.bind(&migration.version)
+			.bind(&migration.checksum)
+			.execute(&mut transaction)
+			.await?;
+
+		info!("marked `{}` as applied.", migration.version);
+		count += 1;
+	}
+
+	transaction.commit().await?;
+
+	Ok(count)
+}
+
+/// Migrations `up` would apply, in order. Refuses to apply migrations that are older than ones
+/// that have already been applied, since they might depend on each other.
+fn pending<'a>(
+	migrations: &'a [Migration],
+	applied: &HashMap<String, AppliedMigration>,
+	to: Option<&str>,
+) -> Eyre<Vec<&'a Migration>> {
+	if let Some(to) = to {
+		if !migrations
+			.iter()
+			.any(|migration| migration.version == to)
+		{
+			return Err(eyre!("Unknown migration `{to}`."));
+		}
+
+		if applied.contains_key(to) {
+			return Err(eyre!("`{to}` has already been applied."));
+		}
+	}
+
+	let latest = applied.keys().max();
+	let pending = migrations
+		.iter()
+		.filter(|migration| !applied.contains_key(&migration.version))
+		.filter(|migration| to.is_none_or(|to| migration.version.as_str() <= to))
+		.collect::<Vec<_>>();
+
+	if let (Some(latest), Some(first)) = (latest, pending.first()) {
+		if &first.version < latest {
+			return Err(eyre!(
+				"`{}` is older than `{latest}`, which has already been applied. Apply it by hand and \
+				 use `baseline` to record it.",
+				first.version
+			));
+		}
+	}
+
+	Ok(pending)
+}
+
+/// Fetches every applied migration, creating `schema_migrations` if necessary.
+async fn applied(pool: &Pool<MySql>) -> Eyre<HashMap<String, AppliedMigration>> {
+	sqlx::query(
+		r#"
+		CREATE TABLE
+		  IF NOT EXISTS schema_migrations (
+		    version VARCHAR(255) NOT NULL PRIMARY KEY,
+		    checksum CHAR(64) NOT NULL,
+		    applied_on DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+		  )
+		"#,
+	)
+	.execute(pool)
+	.await?;
+
+	Ok(sqlx::query_as::<_, AppliedMigration>("SELECT * FROM schema_migrations")
+		.fetch_all(pool)
+		.await?
+		.into_iter()
+		.map(|migration| (migration.version.clone(), migration))
+		.collect())
+}
+
+/// Refuses to continue if applied migrations were changed or deleted since.
+fn verify(migrations: &[Migration], applied: &HashMap<String, AppliedMigration>) -> Eyre<()> {
+	for applied in applied.values() {
+		let Some(migration) = migrations
+			.iter()
+			.find(|migration| migration.version == applied.version)
+		else {
+			return Err(eyre!(
+				"`{}` has been applied, but doesn't exist anymore.",
+				applied.version
+			));
+		};
+
+		if migration.checksum != applied.checksum {
+			return Err(eyre!(
+				"`{}` has been changed since it was applied. Add a new migration instead.",
+				migration.version
+			));
+		}
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use {super::*, std::path::PathBuf};
+
+	/// Creates an empty directory for `.sql` files.
+	fn migrations_dir(name: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("migrations-{}-{name}", std::process::id()));
+		let _ = std::fs::remove_dir_all(&dir);
+		std::fs::create_dir_all(&dir).unwrap();
+		dir
+	}
+
+	fn migration(version: &str) -> Migration {
+		Migration::new(version.to_owned(), format!("-- {version}"), None)
+	}
+
+	fn applied(migrations: &[&Migration]) -> HashMap<String, AppliedMigration> {
+		migrations
+			.iter()
+			.map(|migration| {
+				(
+					migration.version.clone(),
+					AppliedMigration {
+						version: migration.version.clone(),
+						checksum: migration.checksum.clone(),
+						applied_on: NaiveDateTime::default(),
+					},
+				)
+			})
+			.collect()
+	}
+
+	fn versions(migrations: &[&Migration]) -> Vec<String> {
+		migrations
+			.iter()
+			.map(|migration| migration.version.clone())
+			.collect()
+	}
+
+	#[test]
+	fn files_are_split_at_the_down_marker() {
+		let dir = migrations_dir("split");
+		std::fs::write(
+			dir.join("20261018_b.sql"),
+			"CREATE TABLE b (id INT);\n\n-- migrate:down\nDROP TABLE b;\n",
+		)
+		.unwrap();
+		std::fs::write(dir.join("20261018_a.sql"), "INSERT INTO a VALUES (1);\n").unwrap();
+		std::fs::write(dir.join("README.md"), "not a migration").unwrap();
+
+		let migrations = migrations(&dir).unwrap();
+		let b = migrations
+			.iter()
+			.find(|migration| migration.version == "20261018_b")
+			.unwrap();
+		assert_eq!(b.up, "CREATE TABLE b (id INT);\n\n");
+		assert_eq!(b.down.as_deref(), Some("\nDROP TABLE b;\n"));
+
+		let a = migrations
+			.iter()
+			.find(|migration| migration.version == "20261018_a")
+			.unwrap();
+		assert_eq!(a.up, "INSERT INTO a VALUES (1);\n");
+		assert_eq!(a.down, None);
+
+		// the down part can be changed without invalidating applied migrations
+		assert_eq!(b.checksum, Migration::new(String::new(), b.up.clone(), None).checksum);
+
+		std::fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[test]
+	fn migrations_are_sorted_by_version() {
+		let dir = migrations_dir("sorted");
+		for version in ["20261018_b", "20230205_maprelease", "20261018_a"] {
+			std::fs::write(dir.join(format!("{version}.sql")), "SELECT 1;").unwrap();
+		}
+
+		let migrations = migrations(&dir).unwrap();
+		let versions = migrations
+			.iter()
+			.map(|migration| migration.version.as_str())
+			.collect::<Vec<_>>();
+
+		assert_eq!(versions.len(), TABLES.len() + 3);
+		assert_eq!(versions[0], "00000000_00_players");
+		assert_eq!(versions[TABLES.len() - 1], "00000000_07_api_keys");
+		assert_eq!(&versions[TABLES.len()..], ["20230205_maprelease", "20261018_a", "20261018_b"]);
+		assert!(versions
+			.windows(2)
+			.all(|pair| pair[0] < pair[1]));
+
+		std::fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[test]
+	fn unchanged_migrations_are_verified() {
+		let migrations = [migration("1"), migration("2")];
+		assert!(verify(&migrations, &applied(&[&migrations[0]])).is_ok());
+	}
+
+	#[test]
+	fn changed_migrations_are_rejected() {
+		let migrations = [migration("1"), migration("2")];
+		let mut applied = applied(&[&migrations[0], &migrations[1]]);
+		applied.get_mut("2").unwrap().checksum = String::from("something else");
+
+		let error = verify(&migrations, &applied).unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("`2` has been changed"));
+	}
+
+	#[test]
+	fn missing_migrations_are_rejected() {
+		let migrations = [migration("1"), migration("2")];
+		let applied = applied(&[&migrations[0], &migration("3")]);
+
+		let error = verify(&migrations, &applied).unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("`3` has been applied, but doesn't exist"));
+	}
+
+	#[test]
+	fn pending_migrations_are_applied_in_order() {
+		let migrations = [migration("1"), migration("2"), migration("3")];
+		let applied = applied(&[&migrations[0]]);
+
+		let all = pending(&migrations, &applied, None).unwrap();
+		assert_eq!(versions(&all), ["2", "3"]);
+
+		let until_2 = pending(&migrations, &applied, Some("2")).unwrap();
+		assert_eq!(versions(&until_2), ["2"]);
+	}
+
+	#[test]
+	fn migrations_older_than_the_latest_are_refused() {
+		let migrations = [migration("1"), migration("2"), migration("3")];
+		let applied = applied(&[&migrations[0], &migrations[2]]);
+
+		let error = pending(&migrations, &applied, None).unwrap_err();
+		assert!(error
+			.to_string()
+			.contains("`2` is older than `3`"));
+	}
+
+	#[test]
+	fn unknown_or_applied_targets_are_refused() {
+		let migrations = [migration("1"), migration("2")];
+		let applied = applied(&[&migrations[0]]);
+
+		assert!(pending(&migrations, &applied, Some("4")).is_err());
+		assert!(pending(&migrations, &applied, Some("1")).is_err());
+	}
+
+	/// Map releases only insert data and were written before migrations could be rolled back.
+	const WITHOUT_DOWN: [&str; 2] = ["20230205_maprelease", "20230312_maprelease"];
+
+	#[test]
+	fn migrations_can_be_rolled_back() {
+		let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../migrations");
+
+		for migration in migrations(&dir).unwrap() {
+			if WITHOUT_DOWN.contains(&migration.version.as_str()) {
+				continue;
+			}
+
+			assert!(migration.down.is_some(), "`{}` has no down section", migration.version);
+		}
+	}
+}
@@ -0,0 +1,14 @@
+/// The table as it was first created, `migrations/20261018_api_key_scopes.sql` adds the
+/// remaining columns.
+pub const fn up() -> &'static str {
+	r#"
+CREATE TABLE
+  IF NOT EXISTS api_keys (
+    api_key VARCHAR(64) NOT NULL PRIMARY KEY
+  );
+"#
+}
+
+pub const fn down() -> &'static str {
+	r#"DROP TABLE api_keys"#
+}
@@ -1,7 +1,7 @@
 use {
 	crate::MAGIC_NUMBER,
 	color_eyre::Result as Eyre,
-	log::info,
+	database::crd::update::MapperData,
 	serde::{Deserialize, Serialize},
 	sqlx::{MySql, Pool},
 };
@@ -36,72 +36,26 @@ pub enum InputKind {
 }
 
 pub async fn update(data: InputKind, pool: &Pool<MySql>) -> Eyre<usize> {
-	let mut transaction = pool.begin().await?;
-
-	match data {
-		InputKind::KZGO(data) => {
-			for (i, KZGOInput { name, mapper_id }) in data.iter().enumerate() {
-				let Ok(mapper_id) = mapper_id.parse::<u64>() else {
-					continue;
-				};
-
-				if mapper_id <= MAGIC_NUMBER {
-					continue;
-				}
-
-				let mapper_id = mapper_id - MAGIC_NUMBER;
-
-				sqlx::query(&format!(
-					r#"
-			UPDATE maps
-			SET created_by = {mapper_id}
-			WHERE name = "{name}"
-			"#
-				))
-				.execute(&mut transaction)
-				.await?;
-
-				info!("{} / {}", i + 1, data.len());
-			}
-
-			transaction.commit().await?;
-			Ok(data.len())
-		}
-		InputKind::Zero(data) => {
-			for (
-				i,
-				ZeroInput {
-					name,
-					mapper_steamid64,
-					..
-				},
-			) in data.iter().enumerate()
-			{
-				let Ok(mapper_id) = mapper_steamid64.parse::<u64>() else {
-					continue;
-				};
-
-				if mapper_id <= MAGIC_NUMBER {
-					continue;
-				}
-
-				let mapper_id = mapper_id - MAGIC_NUMBER;
-
-				sqlx::query(&format!(
-					r#"
-					UPDATE maps
-					SET created_by = {mapper_id}
-					WHERE name = "{name}"
-					"#
-				))
-				.execute(&mut transaction)
-				.await?;
-
-				info!("{} / {}", i + 1, data.len());
-			}
-
-			transaction.commit().await?;
-			Ok(data.len())
-		}
-	}
+	let mappers = match data {
+		InputKind::KZGO(data) => data
+			.into_iter()
+			.map(|KZGOInput { name, mapper_id }| (name, mapper_id))
+			.collect::<Vec<_>>(),
+		InputKind::Zero(data) => data
+			.into_iter()
+			.map(|input| (input.name, input.mapper_steamid64))
+			.collect(),
+	};
+
+	let mappers = mappers
+		.into_iter()
+		.filter_map(|(map_name, mapper_id)| {
+			let mapper_id = mapper_id.parse::<u64>().ok()?;
+			(mapper_id > MAGIC_NUMBER).then(|| (map_name, (mapper_id - MAGIC_NUMBER) as u32))
+		})
+		.collect::<Vec<MapperData>>();
+
+	let updated = database::crd::update::set_mappers(&mappers, pool).await?;
+
+	Ok(updated as usize)
 }
@@ -11,3 +11,9 @@ pub mod courses;
 pub mod records;
 
 pub mod mappers;
+
+pub mod points;
+
+pub mod streamers;
+
+pub mod api_keys;
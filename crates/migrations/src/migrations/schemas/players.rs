@@ -55,6 +55,8 @@ impl TryFrom<util::Player> for PlayerSchema {
 	}
 }
 
+/// The table as it was first created, `migrations/20261018_player_name_index.sql` adds the
+/// indexes on `name`.
 pub const fn up() -> &'static str {
 	r#"
 CREATE TABLE
@@ -96,6 +98,21 @@ pub async fn insert(data: &[PlayerSchema], pool: &Pool<MySql>) -> Eyre<usize> {
 		.execute(&mut transaction)
 		.await?;
 
+		sqlx::query(&format!(
+			r#"
+			INSERT INTO player_names
+			  (player_id, name)
+			VALUES
+			  ({}, "{}")
+			ON DUPLICATE KEY UPDATE
+			  last_seen = GREATEST(last_seen, VALUES(last_seen))
+			"#,
+			id,
+			sanitize(name),
+		))
+		.execute(&mut transaction)
+		.await?;
+
 		info!("{} / {}", i + 1, data.len());
 	}
 
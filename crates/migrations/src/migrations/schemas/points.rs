@@ -0,0 +1,8 @@
+use {
+	color_eyre::Result as Eyre,
+	sqlx::{MySql, Pool},
+};
+
+pub async fn recalculate(pool: &Pool<MySql>) -> Eyre<usize> {
+	database::points::recalculate_all(pool).await
+}
@@ -1,6 +1,6 @@
 use {
 	crate::{
-		migrations::{self, schemas::courses::CourseSchema, util},
+		migrations::{self, sanitize, schemas::courses::CourseSchema, util},
 		MAGIC_NUMBER,
 	},
 	chrono::{DateTime, TimeZone, Utc},
@@ -24,6 +24,7 @@ pub struct RecordSchema {
 	pub __server_name: String,
 	pub __stage: u8,
 	pub __map_name: String,
+	pub __player_name: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +69,7 @@ impl TryFrom<ElasticRecord> for RecordSchema {
 			__server_name: value.server_name,
 			__stage: value.stage,
 			__map_name: value.map_name,
+			__player_name: Some(value.player_name),
 		})
 	}
 }
@@ -103,6 +105,7 @@ impl TryFrom<Record> for RecordSchema {
 			__map_name: value
 				.map_name
 				.unwrap_or_else(|| String::from("unknown")),
+			__player_name: value.player_name,
 		})
 	}
 }
@@ -161,6 +164,7 @@ pub async fn insert(
 			__server_name,
 			__stage,
 			__map_name,
+			__player_name,
 		},
 	) in data.iter().enumerate()
 	{
@@ -263,6 +267,26 @@ pub async fn insert(
 		.execute(&mut transaction)
 		.await?;
 
+		if let Some(player_name) = __player_name {
+			if player_id != 0 {
+				sqlx::query(&format!(
+					r#"
+					INSERT INTO player_names
+					  (player_id, name, first_seen, last_seen)
+					VALUES
+					  ({player_id}, "{0}", "{1}", "{1}")
+					ON DUPLICATE KEY UPDATE
+					  first_seen = LEAST(first_seen, VALUES(first_seen)),
+					  last_seen = GREATEST(last_seen, VALUES(last_seen))
+					"#,
+					sanitize(player_name),
+					created_on.rsplit_once(' ').unwrap().0
+				))
+				.execute(&mut transaction)
+				.await?;
+			}
+		}
+
 		info!("{} / {}", i + 1, data.len());
 	}
 
@@ -0,0 +1,19 @@
+/// The table as it was first created, `migrations/20261018_streamer_channels.sql` adds
+/// `channel_name`.
+pub const fn up() -> &'static str {
+	r#"
+CREATE TABLE
+  IF NOT EXISTS streamers (
+    api_key VARCHAR(255) NOT NULL PRIMARY KEY,
+    player_name VARCHAR(255),
+    steam_id VARCHAR(255),
+    mode VARCHAR(255),
+    map_name VARCHAR(255),
+    map_tier TINYINT UNSIGNED
+  );
+"#
+}
+
+pub const fn down() -> &'static str {
+	r#"DROP TABLE streamers"#
+}
@@ -1,7 +1,9 @@
 use {
+	crate::{crd::update::update_ban_status, points, schemas::RecordRow},
 	chrono::{DateTime, Utc},
 	color_eyre::Result as Eyre,
-	sqlx::{MySql, Pool, QueryBuilder},
+	sqlx::{query_builder::Separated, MySql, Pool, QueryBuilder, Transaction},
+	std::collections::HashMap,
 };
 
 pub type ModeData = (u8, String, DateTime<Utc>);
@@ -57,6 +59,129 @@ pub async fn insert_players(players: &[PlayerData], pool: &Pool<MySql>) -> Eyre<
 	Ok(())
 }
 
+pub type PlayerNameData = (u32, String, DateTime<Utc>);
+/// Records that a player used a name at a given time. Already known names only get their
+/// `first_seen` / `last_seen` range extended.
+pub async fn insert_player_names(names: &[PlayerNameData], pool: &Pool<MySql>) -> Eyre<()> {
+	if names.is_empty() {
+		return Ok(());
+	}
+
+	let mut transaction = pool.begin().await?;
+
+	let mut query = QueryBuilder::new(
+		r#"
+		INSERT INTO player_names
+		  (player_id, name, first_seen, last_seen)
+		"#,
+	);
+	query
+		.push_values(names, |mut query, (player_id, name, seen_on)| {
+			query
+				.push_bind(player_id)
+				.push_bind(name)
+				.push_bind(seen_on)
+				.push_bind(seen_on);
+		})
+		.push(
+			r#"
+			ON DUPLICATE KEY UPDATE
+			  first_seen = LEAST(first_seen, VALUES(first_seen)),
+			  last_seen = GREATEST(last_seen, VALUES(last_seen))
+			"#,
+		)
+		.build()
+		.execute(&mut transaction)
+		.await?;
+
+	transaction.commit().await?;
+
+	Ok(())
+}
+
+pub type BanData = (
+	u32,
+	u32,
+	String,
+	String,
+	u16,
+	DateTime<Utc>,
+	Option<DateTime<Utc>>,
+	DateTime<Utc>,
+);
+/// Inserts or updates ban entries and recomputes `players.is_banned` for the affected players.
+pub async fn insert_bans(bans: &[BanData], pool: &Pool<MySql>) -> Eyre<()> {
+	if bans.is_empty() {
+		return Ok(());
+	}
+
+	let mut transaction = pool.begin().await?;
+
+	let mut query = QueryBuilder::new(
+		r#"
+		INSERT INTO bans
+		  (id, player_id, ban_type, reason, server_id, created_on, expires_on, updated_on)
+		"#,
+	);
+	query
+		.push_values(
+			bans,
+			|mut query,
+			 (id, player_id, ban_type, reason, server_id, created_on, expires_on, updated_on)| {
+				query
+					.push_bind(id)
+					.push_bind(player_id)
+					.push_bind(ban_type)
+					.push_bind(reason)
+					.push_bind(server_id)
+					.push_bind(created_on)
+					.push_bind(expires_on)
+					.push_bind(updated_on);
+			},
+		)
+		.push(
+			r#"
+			ON DUPLICATE KEY UPDATE
+			  ban_type = VALUES(ban_type),
+			  reason = VALUES(reason),
+			  expires_on = VALUES(expires_on),
+			  updated_on = VALUES(updated_on)
+			"#,
+		)
+		.build()
+		.execute(&mut transaction)
+		.await?;
+
+	transaction.commit().await?;
+
+	update_ban_status(pool).await?;
+
+	Ok(())
+}
+
+pub type ApiKeyData = (String, String, String, u32);
+pub async fn insert_api_key(
+	(api_key, name, scopes, requests_per_minute): &ApiKeyData,
+	pool: &Pool<MySql>,
+) -> Eyre<()> {
+	sqlx::query(
+		r#"
+		INSERT INTO api_keys
+		  (api_key, name, scopes, requests_per_minute)
+		VALUES
+		  (?, ?, ?, ?)
+		"#,
+	)
+	.bind(api_key)
+	.bind(name)
+	.bind(scopes)
+	.bind(requests_per_minute)
+	.execute(pool)
+	.await?;
+
+	Ok(())
+}
+
 pub type ServerData = (u16, String, u32, u32);
 pub async fn insert_servers(servers: &[ServerData], pool: &Pool<MySql>) -> Eyre<()> {
 	let mut transaction = pool.begin().await?;
@@ -196,7 +321,524 @@ pub async fn insert_records(records: &[RecordData], pool: &Pool<MySql>) -> Eyre<
 		.execute(&mut transaction)
 		.await?;
 
+	let mut changes = points::Changes::new();
+	for (_, course_id, mode_id, _, _, time, teleports, _) in records {
+		points::track(&mut changes, (*course_id, *mode_id, *teleports > 0), *time);
+	}
+
+	points::recalculate(&changes, &mut transaction).await?;
+
 	transaction.commit().await?;
 
 	Ok(())
 }
+
+/// How many rows are sent to the database per `INSERT`.
+pub const CHUNK_SIZE: usize = 1000;
+
+/// What the `upsert_*` functions do with rows whose primary key already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conflict {
+	/// Keep the existing row.
+	Skip,
+	/// Replace the existing row with the new one.
+	Overwrite,
+	/// Keep whichever row has the faster time. Behaves like [`Conflict::Skip`] for tables
+	/// without a `time` column.
+	KeepFaster,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UpsertCount {
+	pub inserted: u64,
+	pub updated: u64,
+	pub skipped: u64,
+}
+
+impl std::ops::AddAssign for UpsertCount {
+	fn add_assign(&mut self, rhs: Self) {
+		self.inserted += rhs.inserted;
+		self.updated += rhs.updated;
+		self.skipped += rhs.skipped;
+	}
+}
+
+/// Like [`insert_players`], but doesn't fail on existing players. Overwriting only changes the
+/// name; `is_banned` is left to [`update_ban_status`].
+pub async fn upsert_players(
+	players: &[PlayerData],
+	conflict: Conflict,
+	pool: &Pool<MySql>,
+) -> Eyre<UpsertCount> {
+	let mut transaction = pool.begin().await?;
+
+	let (count, _) = upsert(
+		Table {
+			name: "players",
+			columns: "id, name, is_banned",
+			overwrite: &["name"],
+		},
+		players,
+		|(id, ..)| *id,
+		None,
+		|mut query, (id, name, is_banned)| {
+			query
+				.push_bind(id)
+				.push_bind(name)
+				.push_bind(is_banned);
+		},
+		conflict,
+		&mut transaction,
+	)
+	.await?;
+
+	transaction.commit().await?;
+
+	Ok(count)
+}
+
+pub async fn upsert_servers(
+	servers: &[ServerData],
+	conflict: Conflict,
+	pool: &Pool<MySql>,
+) -> Eyre<UpsertCount> {
+	let mut transaction = pool.begin().await?;
+
+	let (count, _) = upsert(
+		Table {
+			name: "servers",
+			columns: "id, name, owned_by, approved_by",
+			overwrite: &["name", "owned_by", "approved_by"],
+		},
+		servers,
+		|(id, ..)| u32::from(*id),
+		None,
+		|mut query, (id, name, owned_by, approved_by)| {
+			query
+				.push_bind(id)
+				.push_bind(name)
+				.push_bind(owned_by)
+				.push_bind(approved_by);
+		},
+		conflict,
+		&mut transaction,
+	)
+	.await?;
+
+	transaction.commit().await?;
+
+	Ok(count)
+}
+
+/// Like [`insert_maps`], but doesn't fail on existing maps. `created_on` is never overwritten.
+pub async fn upsert_maps(
+	maps: &[MapData],
+	conflict: Conflict,
+	pool: &Pool<MySql>,
+) -> Eyre<UpsertCount> {
+	let mut transaction = pool.begin().await?;
+
+	let (count, _) = upsert(
+		Table {
+			name: "maps",
+			columns:
+				"id, name, courses, validated, filesize, created_by, approved_by, created_on, \
+			          updated_on",
+			overwrite: &[
+				"name",
+				"courses",
+				"validated",
+				"filesize",
+				"created_by",
+				"approved_by",
+				"updated_on",
+			],
+		},
+		maps,
+		|(id, ..)| u32::from(*id),
+		None,
+		|mut query,
+		 (
+			id,
+			name,
+			courses,
+			validated,
+			filesize,
+			created_by,
+			approved_by,
+			created_on,
+			updated_on,
+		)| {
+			query
+				.push_bind(id)
+				.push_bind(name)
+				.push_bind(courses)
+				.push_bind(validated)
+				.push_bind(filesize)
+				.push_bind(created_by)
+				.push_bind(approved_by)
+				.push_bind(created_on)
+				.push_bind(updated_on);
+		},
+		conflict,
+		&mut transaction,
+	)
+	.await?;
+
+	transaction.commit().await?;
+
+	Ok(count)
+}
+
+pub async fn upsert_courses(
+	courses: &[CourseData],
+	conflict: Conflict,
+	pool: &Pool<MySql>,
+) -> Eyre<UpsertCount> {
+	let mut transaction = pool.begin().await?;
+
+	let (count, _) = upsert(
+		Table {
+			name: "courses",
+			columns: "id, map_id, stage, kzt, kzt_difficulty, skz, skz_difficulty, vnl, \
+			          vnl_difficulty",
+			overwrite: &[
+				"kzt",
+				"kzt_difficulty",
+				"skz",
+				"skz_difficulty",
+				"vnl",
+				"vnl_difficulty",
+			],
+		},
+		courses,
+		|(id, ..)| *id,
+		None,
+		|mut query,
+		 (id, map_id, stage, kzt, kzt_difficulty, skz, skz_difficulty, vnl, vnl_difficulty)| {
+			query
+				.push_bind(id)
+				.push_bind(map_id)
+				.push_bind(stage)
+				.push_bind(kzt)
+				.push_bind(kzt_difficulty)
+				.push_bind(skz)
+				.push_bind(skz_difficulty)
+				.push_bind(vnl)
+				.push_bind(vnl_difficulty);
+		},
+		conflict,
+		&mut transaction,
+	)
+	.await?;
+
+	transaction.commit().await?;
+
+	Ok(count)
+}
+
+/// Like [`insert_records`], but doesn't fail on existing records. Points are only recalculated
+/// for leaderboards that actually changed, including the ones overwritten records were moved away
+/// from.
+pub async fn upsert_records(
+	records: &[RecordData],
+	conflict: Conflict,
+	pool: &Pool<MySql>,
+) -> Eyre<UpsertCount> {
+	let mut transaction = pool.begin().await?;
+
+	// overwritten records can move to a different leaderboard, which needs new points as well
+	let mut previous = HashMap::new();
+	if conflict != Conflict::Skip {
+		for chunk in records.chunks(CHUNK_SIZE) {
+			let mut query = QueryBuilder::new("SELECT * FROM records WHERE id IN (");
+			let mut ids = query.separated(", ");
+			for (id, ..) in chunk {
+				ids.push_bind(*id);
+			}
+			query.push(")");
+
+			previous.extend(
+				query
+					.build_query_as::<RecordRow>()
+					.fetch_all(&mut transaction)
+					.await?
+					.into_iter()
+					.map(|record| (record.id, record)),
+			);
+		}
+	}
+
+	let (count, changed) = upsert(
+		Table {
+			name: "records",
+			columns: "id, course_id, mode_id, player_id, server_id, time, teleports, created_on",
+			// `time` needs to come last for `Conflict::KeepFaster`
+			overwrite: &[
+				"course_id",
+				"mode_id",
+				"player_id",
+				"server_id",
+				"teleports",
+				"created_on",
+				"time",
+			],
+		},
+		records,
+		|(id, ..)| *id,
+		Some(|(_, _, _, _, _, time, ..)| *time),
+		|mut query, (id, course_id, mode_id, player_id, server_id, time, teleports, created_on)| {
+			query
+				.push_bind(id)
+				.push_bind(course_id)
+				.push_bind(mode_id)
+				.push_bind(player_id)
+				.push_bind(server_id)
+				.push_bind(time)
+				.push_bind(teleports)
+				.push_bind(created_on);
+		},
+		conflict,
+		&mut transaction,
+	)
+	.await?;
+
+	let mut changes = points::Changes::new();
+	for (id, course_id, mode_id, _, _, time, teleports, _) in changed {
+		points::track(&mut changes, (*course_id, *mode_id, *teleports > 0), *time);
+
+		if let Some(record) = previous.get(id) {
+			let leaderboard = (record.course_id, record.mode_id, record.teleports > 0);
+			points::track(&mut changes, leaderboard, record.time);
+		}
+	}
+
+	points::recalculate(&changes, &mut transaction).await?;
+
+	transaction.commit().await?;
+
+	Ok(count)
+}
+
+struct Table {
+	name: &'static str,
+	/// Column list for the `INSERT`, starting with the primary key.
+	columns: &'static str,
+	/// Columns that get replaced on conflict.
+	overwrite: &'static [&'static str],
+}
+
+/// Upserts `rows` in chunks of [`CHUNK_SIZE`]. Returns the counts and every row that was either
+/// inserted or updated.
+///
+/// The affected row count MySQL reports can't tell inserted rows from unchanged ones, so existing
+/// keys are looked up first.
+async fn upsert<'a, T>(
+	table: Table,
+	rows: &'a [T],
+	id: fn(&T) -> u32,
+	time: Option<fn(&T) -> f64>,
+	mut push_row: impl FnMut(Separated<'_, 'a, MySql, &'static str>, &'a T),
+	conflict: Conflict,
+	transaction: &mut Transaction<'_, MySql>,
+) -> Eyre<(UpsertCount, Vec<&'a T>)> {
+	let mut count = UpsertCount::default();
+	let mut changed = Vec::new();
+
+	for chunk in rows.chunks(CHUNK_SIZE) {
+		let time_column = if time.is_some() { "time" } else { "NULL" };
+		let mut query = QueryBuilder::new(format!(
+			"SELECT id, {time_column} FROM {} WHERE id IN (",
+			table.name
+		));
+		let mut ids = query.separated(", ");
+		for row in chunk {
+			ids.push_bind(id(row));
+		}
+		query.push(")");
+
+		// id -> time
+		let existing = query
+			.build_query_as::<(u32, Option<f64>)>()
+			.fetch_all(&mut *transaction)
+			.await?
+			.into_iter()
+			.collect::<HashMap<_, _>>();
+
+		let (chunk_count, chunk_changed) = classify(chunk, existing, id, time, conflict);
+		count += chunk_count;
+		changed.extend(chunk_changed);
+
+		let mut query =
+			QueryBuilder::new(format!("INSERT INTO {} ({}) ", table.name, table.columns));
+		query.push_values(chunk, &mut push_row);
+		query.push(" ON DUPLICATE KEY UPDATE ");
+
+		match conflict {
+			Conflict::Overwrite => {
+				let mut columns = query.separated(", ");
+				for column in table.overwrite {
+					columns.push(format!("{column} = VALUES({column})"));
+				}
+			}
+			Conflict::KeepFaster if time.is_some() => {
+				// assignments are evaluated left to right, so `time` has to be updated last
+				let mut columns = query.separated(", ");
+				for column in table.overwrite {
+					columns.push(if *column == "time" {
+						String::from("time = LEAST(time, VALUES(time))")
+					} else {
+						format!("{column} = IF(VALUES(time) < time, VALUES({column}), {column})")
+					});
+				}
+			}
+			// unlike `INSERT IGNORE`, this still fails on foreign key errors
+			Conflict::Skip | Conflict::KeepFaster => {
+				query.push("id = id");
+			}
+		}
+
+		query
+			.build()
+			.execute(&mut *transaction)
+			.await?;
+	}
+
+	Ok((count, changed))
+}
+
+/// Decides what happens to every row in `rows`. `existing` maps the IDs that are already in the
+/// database to their time, if the table has one. Returns the counts and every row that gets
+/// inserted or updated.
+fn classify<T>(
+	rows: &[T],
+	mut existing: HashMap<u32, Option<f64>>,
+	id: fn(&T) -> u32,
+	time: Option<fn(&T) -> f64>,
+	conflict: Conflict,
+) -> (UpsertCount, Vec<&T>) {
+	let mut count = UpsertCount::default();
+	let mut changed = Vec::new();
+
+	for row in rows {
+		let new_time = time.map(|time| time(row));
+		let is_change = match existing.get(&id(row)) {
+			None => {
+				count.inserted += 1;
+				true
+			}
+			Some(_) if conflict == Conflict::Overwrite => {
+				count.updated += 1;
+				true
+			}
+			Some(Some(old_time))
+				if conflict == Conflict::KeepFaster
+					&& new_time.is_some_and(|new_time| new_time < *old_time) =>
+			{
+				count.updated += 1;
+				true
+			}
+			Some(_) => {
+				count.skipped += 1;
+				false
+			}
+		};
+
+		if is_change {
+			// the same ID could appear again later in `rows`
+			existing.insert(id(row), new_time);
+			changed.push(row);
+		}
+	}
+
+	(count, changed)
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::{classify, Conflict, UpsertCount},
+		std::collections::HashMap,
+	};
+
+	type Row = (u32, f64);
+
+	fn run(rows: &[Row], existing: &[Row], conflict: Conflict) -> (UpsertCount, Vec<Row>) {
+		let existing = existing
+			.iter()
+			.map(|&(id, time)| (id, Some(time)))
+			.collect::<HashMap<_, _>>();
+
+		let (count, changed) =
+			classify(rows, existing, |(id, _)| *id, Some(|(_, time)| *time), conflict);
+
+		(count, changed.into_iter().copied().collect())
+	}
+
+	fn counts(inserted: u64, updated: u64, skipped: u64) -> UpsertCount {
+		UpsertCount {
+			inserted,
+			updated,
+			skipped,
+		}
+	}
+
+	#[test]
+	fn new_rows_are_inserted() {
+		for conflict in [Conflict::Skip, Conflict::Overwrite, Conflict::KeepFaster] {
+			let (count, changed) = run(&[(1, 10.0), (2, 20.0)], &[], conflict);
+			assert_eq!(count, counts(2, 0, 0));
+			assert_eq!(changed, [(1, 10.0), (2, 20.0)]);
+		}
+	}
+
+	#[test]
+	fn skip_keeps_existing_rows() {
+		let (count, changed) = run(&[(1, 5.0), (2, 20.0)], &[(1, 10.0)], Conflict::Skip);
+		assert_eq!(count, counts(1, 0, 1));
+		assert_eq!(changed, [(2, 20.0)]);
+	}
+
+	#[test]
+	fn overwrite_updates_existing_rows() {
+		let (count, changed) = run(&[(1, 15.0), (2, 20.0)], &[(1, 10.0)], Conflict::Overwrite);
+		assert_eq!(count, counts(1, 1, 0));
+		assert_eq!(changed, [(1, 15.0), (2, 20.0)]);
+	}
+
+	#[test]
+	fn keep_faster_only_updates_faster_rows() {
+		let (count, changed) =
+			run(&[(1, 5.0), (2, 25.0), (3, 30.0)], &[(1, 10.0), (2, 20.0)], Conflict::KeepFaster);
+		assert_eq!(count, counts(1, 1, 1));
+		assert_eq!(changed, [(1, 5.0), (3, 30.0)]);
+	}
+
+	#[test]
+	fn keep_faster_without_time_skips() {
+		let existing = HashMap::from([(1, None)]);
+		let (count, changed) =
+			classify(&[(1, 5.0)], existing, |(id, _): &Row| *id, None, Conflict::KeepFaster);
+		assert_eq!(count, counts(0, 0, 1));
+		assert!(changed.is_empty());
+	}
+
+	#[test]
+	fn duplicate_ids_within_a_batch() {
+		let rows = [(1, 20.0), (1, 10.0), (1, 15.0)];
+
+		// the first one is inserted, the others conflict with it
+		let (count, changed) = run(&rows, &[], Conflict::Skip);
+		assert_eq!(count, counts(1, 0, 2));
+		assert_eq!(changed, [(1, 20.0)]);
+
+		let (count, changed) = run(&rows, &[], Conflict::Overwrite);
+		assert_eq!(count, counts(1, 2, 0));
+		assert_eq!(changed, rows);
+
+		// compared against the fastest time so far, not the one in the database
+		let (count, changed) = run(&rows, &[], Conflict::KeepFaster);
+		assert_eq!(count, counts(1, 1, 1));
+		assert_eq!(changed, [(1, 20.0), (1, 10.0)]);
+	}
+}
@@ -0,0 +1,57 @@
+use {
+	crate::{points, schemas::RecordRow},
+	color_eyre::Result as Eyre,
+	sqlx::{MySql, Pool, QueryBuilder},
+	std::collections::HashMap,
+};
+
+/// Deletes a record and recalculates the leaderboard it was on. Returns whether the record
+/// existed.
+pub async fn delete_record(record_id: u32, pool: &Pool<MySql>) -> Eyre<bool> {
+	let mut transaction = pool.begin().await?;
+
+	let Some(record) = sqlx::query_as::<_, RecordRow>("SELECT * FROM records WHERE id = ?")
+		.bind(record_id)
+		.fetch_optional(&mut transaction)
+		.await?
+	else {
+		return Ok(false);
+	};
+
+	for mut query in delete_record_queries(record_id) {
+		query
+			.build()
+			.execute(&mut transaction)
+			.await?;
+	}
+
+	let leaderboard = (record.course_id, record.mode_id, record.teleports > 0);
+	points::recalculate(&HashMap::from([(leaderboard, record.time)]), &mut transaction).await?;
+
+	transaction.commit().await?;
+
+	Ok(true)
+}
+
+/// Points reference the record, so they have to go first.
+fn delete_record_queries(record_id: u32) -> [QueryBuilder<'static, MySql>; 2] {
+	let mut points = QueryBuilder::new("DELETE FROM points WHERE record_id = ");
+	points.push_bind(record_id);
+
+	let mut records = QueryBuilder::new("DELETE FROM records WHERE id = ");
+	records.push_bind(record_id);
+
+	[points, records]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::delete_record_queries;
+
+	#[test]
+	fn points_are_deleted_before_the_record() {
+		let [points, records] = delete_record_queries(42);
+		assert_eq!(points.sql(), "DELETE FROM points WHERE record_id = ?");
+		assert_eq!(records.sql(), "DELETE FROM records WHERE id = ?");
+	}
+}
@@ -1,2 +1,4 @@
 pub mod create;
+pub mod delete;
 pub mod read;
+pub mod update;
@@ -3,18 +3,30 @@ use {
 	color_eyre::{eyre::eyre, Result as Eyre},
 	gokz_rs::prelude::*,
 	log::debug,
-	sqlx::{MySql, Pool},
+	sqlx::{MySql, Pool, QueryBuilder},
 };
 
+/// Escapes `\`, `%` and `_` so `input` only matches itself inside a `LIKE` pattern.
+pub fn escape_like(input: &str) -> String {
+	let mut escaped = String::with_capacity(input.len());
+	for c in input.chars() {
+		if matches!(c, '\\' | '%' | '_') {
+			escaped.push('\\');
+		}
+		escaped.push(c);
+	}
+	escaped
+}
+
 pub async fn get_mode(mode: Mode, pool: &Pool<MySql>) -> Eyre<ModeRow> {
 	debug!("Mode: {mode:?}");
-	Ok(sqlx::query_as::<_, ModeRow>(&format!(
+	Ok(sqlx::query_as::<_, ModeRow>(
 		r#"
 		SELECT * FROM modes
-		WHERE id = {}
+		WHERE id = ?
 		"#,
-		mode as u8
-	))
+	)
+	.bind(mode as u8)
 	.fetch_one(pool)
 	.await?)
 }
@@ -27,48 +39,81 @@ pub async fn get_modes(pool: &Pool<MySql>) -> Eyre<Vec<ModeRow>> {
 
 pub async fn get_player(player: PlayerIdentifier, pool: &Pool<MySql>) -> Eyre<PlayerRow> {
 	debug!("Player: {player:?}");
-	let filter = match player {
+	let account_id = match player {
 		PlayerIdentifier::Name(player_name) => {
-			format!(r#"name LIKE "{player_name}%""#)
+			let pattern = format!("{}%", escape_like(&player_name));
+
+			// exact matches first, then the alphabetically first prefix match
+			match sqlx::query_as::<_, PlayerRow>(
+				r#"
+				SELECT * FROM players
+				WHERE name LIKE ?
+				ORDER BY name = ? DESC, name ASC
+				LIMIT 1
+				"#,
+			)
+			.bind(&pattern)
+			.bind(&player_name)
+			.fetch_one(pool)
+			.await
+			{
+				Ok(player) => return Ok(player),
+				Err(sqlx::Error::RowNotFound) => {}
+				Err(why) => return Err(why.into()),
+			}
+
+			// the player might have changed their name since
+			return Ok(sqlx::query_as::<_, PlayerRow>(
+				r#"
+				SELECT p.* FROM players AS p
+				JOIN player_names AS n ON n.player_id = p.id
+				WHERE n.name LIKE ?
+				ORDER BY n.last_seen DESC
+				LIMIT 1
+				"#,
+			)
+			.bind(&pattern)
+			.fetch_one(pool)
+			.await?);
 		}
 		PlayerIdentifier::SteamID(steam_id) => {
-			let account_id =
-				steam_id_to_account_id(&steam_id.to_string()).ok_or(eyre!("Bad SteamID"))?;
-			format!("id = {account_id}")
-		}
-		PlayerIdentifier::SteamID64(steam_id64) => {
-			let account_id = steam_id64_to_account_id(steam_id64)?;
-			format!("id = {account_id}")
+			steam_id_to_account_id(&steam_id.to_string()).ok_or(eyre!("Bad SteamID"))?
 		}
+		PlayerIdentifier::SteamID64(steam_id64) => steam_id64_to_account_id(steam_id64)?,
 	};
 
-	Ok(sqlx::query_as::<_, PlayerRow>(&format!(
+	Ok(sqlx::query_as::<_, PlayerRow>(
 		r#"
 		SELECT * FROM players
-		WHERE {filter}
-		"#
-	))
+		WHERE id = ?
+		"#,
+	)
+	.bind(account_id)
 	.fetch_one(pool)
 	.await?)
 }
 
 pub async fn get_server(server: String, pool: &Pool<MySql>) -> Eyre<ServerRow> {
 	debug!("Server: {server:?}");
-	let filter = if let Ok(server_id) = server.parse::<u16>() {
-		format!("id = {server_id}")
+	Ok(server_query(&server)
+		.build_query_as::<ServerRow>()
+		.fetch_one(pool)
+		.await?)
+}
+
+fn server_query(server: &str) -> QueryBuilder<'static, MySql> {
+	let mut query = QueryBuilder::new("SELECT * FROM servers WHERE ");
+
+	if let Ok(server_id) = server.parse::<u16>() {
+		query.push("id = ").push_bind(server_id);
 	} else {
-		format!(r#"name LIKE "%{server}%""#)
-	};
+		query
+			.push("name LIKE ")
+			.push_bind(format!("%{}%", escape_like(server)));
+	}
 
-	Ok(sqlx::query_as::<_, ServerRow>(&format!(
-		r#"
-		SELECT * FROM servers
-		WHERE {filter}
-		LIMIT 1
-		"#
-	))
-	.fetch_one(pool)
-	.await?)
+	query.push(" LIMIT 1");
+	query
 }
 
 pub async fn get_servers(pool: &Pool<MySql>) -> Eyre<Vec<ServerRow>> {
@@ -79,20 +124,28 @@ pub async fn get_servers(pool: &Pool<MySql>) -> Eyre<Vec<ServerRow>> {
 
 pub async fn get_map(map: MapIdentifier, pool: &Pool<MySql>) -> Eyre<MapRow> {
 	debug!("Map: {map:?}");
-	let filter = match map {
-		MapIdentifier::ID(map_id) => format!("id = {map_id}"),
-		MapIdentifier::Name(map_name) => format!(r#"name LIKE "%{map_name}%""#),
-	};
+	Ok(map_query(map)
+		.build_query_as::<MapRow>()
+		.fetch_one(pool)
+		.await?)
+}
 
-	Ok(sqlx::query_as::<_, MapRow>(&format!(
-		r#"
-		SELECT * FROM maps
-		WHERE {filter}
-		LIMIT 1
-		"#
-	))
-	.fetch_one(pool)
-	.await?)
+fn map_query(map: MapIdentifier) -> QueryBuilder<'static, MySql> {
+	let mut query = QueryBuilder::new("SELECT * FROM maps WHERE ");
+
+	match map {
+		MapIdentifier::ID(map_id) => {
+			query.push("id = ").push_bind(map_id);
+		}
+		MapIdentifier::Name(map_name) => {
+			query
+				.push("name LIKE ")
+				.push_bind(format!("%{}%", escape_like(&map_name)));
+		}
+	}
+
+	query.push(" LIMIT 1");
+	query
 }
 
 pub async fn get_maps(pool: &Pool<MySql>) -> Eyre<Vec<MapRow>> {
@@ -103,35 +156,112 @@ pub async fn get_maps(pool: &Pool<MySql>) -> Eyre<Vec<MapRow>> {
 
 pub async fn get_course(course_id: u32, pool: &Pool<MySql>) -> Eyre<CourseRow> {
 	debug!("Course: {course_id:?}");
-	Ok(sqlx::query_as::<_, CourseRow>(&format!(
+	Ok(sqlx::query_as::<_, CourseRow>(
 		r#"
 		SELECT * FROM courses
-		WHERE id = {course_id}
-		"#
-	))
+		WHERE id = ?
+		"#,
+	)
+	.bind(course_id)
 	.fetch_one(pool)
 	.await?)
 }
 
 pub async fn get_courses(map_id: u16, pool: &Pool<MySql>) -> Eyre<Vec<CourseRow>> {
-	Ok(sqlx::query_as::<_, CourseRow>(&format!(
+	Ok(sqlx::query_as::<_, CourseRow>(
 		r#"
 		SELECT * FROM courses
-		WHERE map_id = {map_id}
-		"#
-	))
+		WHERE map_id = ?
+		"#,
+	)
+	.bind(map_id)
 	.fetch_all(pool)
 	.await?)
 }
 
 pub async fn get_record(record_id: u32, pool: &Pool<MySql>) -> Eyre<RecordRow> {
 	debug!("Record: {record_id:?}");
-	Ok(sqlx::query_as::<_, RecordRow>(&format!(
+	Ok(sqlx::query_as::<_, RecordRow>(
 		r#"
 		SELECT * FROM records
-		WHERE id = {record_id}
-		"#
-	))
+		WHERE id = ?
+		"#,
+	)
+	.bind(record_id)
+	.fetch_one(pool)
+	.await?)
+}
+
+pub async fn get_api_key(api_key: &str, pool: &Pool<MySql>) -> Eyre<ApiKeyRow> {
+	Ok(sqlx::query_as::<_, ApiKeyRow>(
+		r#"
+		SELECT * FROM api_keys
+		WHERE api_key = ?
+		AND revoked_on IS NULL
+		"#,
+	)
+	.bind(api_key)
 	.fetch_one(pool)
 	.await?)
 }
+
+pub async fn get_api_keys(pool: &Pool<MySql>) -> Eyre<Vec<ApiKeyRow>> {
+	Ok(sqlx::query_as::<_, ApiKeyRow>("SELECT * FROM api_keys")
+		.fetch_all(pool)
+		.await?)
+}
+
+#[cfg(test)]
+mod tests {
+	use {
+		super::{escape_like, map_query, server_query},
+		gokz_rs::prelude::MapIdentifier,
+	};
+
+	#[test]
+	fn plain_names_are_unchanged() {
+		assert_eq!(escape_like("AlphaKeks"), "AlphaKeks");
+		assert_eq!(escape_like(""), "");
+	}
+
+	#[test]
+	fn wildcards_are_escaped() {
+		assert_eq!(escape_like("kz_beginnerblock"), "kz\\_beginnerblock");
+		assert_eq!(escape_like("%"), "\\%");
+		assert_eq!(escape_like("100%_done"), "100\\%\\_done");
+	}
+
+	#[test]
+	fn backslashes_are_escaped() {
+		assert_eq!(escape_like("\\"), "\\\\");
+		// otherwise a trailing backslash would escape the `%` we append to the pattern
+		assert_eq!(format!("{}%", escape_like("name\\")), "name\\\\%");
+	}
+
+	#[test]
+	fn quotes_are_not_touched() {
+		// quotes can't end the pattern early since it is always bound, never interpolated
+		assert_eq!(escape_like(r#"" OR 1=1 -- "#), r#"" OR 1=1 -- "#);
+		assert_eq!(escape_like(r#""%' OR '%"#), r#""\%' OR '\%"#);
+	}
+
+	#[test]
+	fn names_are_bound() {
+		let name = r#"kz_" OR 1=1; DROP TABLE maps; -- %"#;
+
+		let query = map_query(MapIdentifier::Name(name.to_owned()));
+		assert_eq!(query.sql(), "SELECT * FROM maps WHERE name LIKE ? LIMIT 1");
+
+		let query = server_query(name);
+		assert_eq!(query.sql(), "SELECT * FROM servers WHERE name LIKE ? LIMIT 1");
+	}
+
+	#[test]
+	fn ids_are_bound() {
+		assert_eq!(
+			map_query(MapIdentifier::ID(42)).sql(),
+			"SELECT * FROM maps WHERE id = ? LIMIT 1"
+		);
+		assert_eq!(server_query("42").sql(), "SELECT * FROM servers WHERE id = ? LIMIT 1");
+	}
+}
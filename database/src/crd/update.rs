@@ -0,0 +1,142 @@
+use {
+	color_eyre::Result as Eyre,
+	sqlx::{MySql, Pool, QueryBuilder},
+};
+
+/// Manually (un)bans a player. This gets overwritten by [`update_ban_status`] if the player has
+/// entries in `bans`.
+pub async fn set_ban(player_id: u32, is_banned: bool, pool: &Pool<MySql>) -> Eyre<u64> {
+	let result = set_ban_query(player_id, is_banned)
+		.build()
+		.execute(pool)
+		.await?;
+
+	Ok(result.rows_affected())
+}
+
+fn set_ban_query(player_id: u32, is_banned: bool) -> QueryBuilder<'static, MySql> {
+	let mut query = QueryBuilder::new("UPDATE players SET is_banned = ");
+	query
+		.push_bind(is_banned)
+		.push(" WHERE id = ")
+		.push_bind(player_id);
+	query
+}
+
+/// Sets `players.is_banned` according to the player's currently active bans. Needs to run
+/// periodically, since bans expire without anything being written to the database.
+pub async fn update_ban_status(pool: &Pool<MySql>) -> Eyre<u64> {
+	let result = sqlx::query(
+		r#"
+		UPDATE players AS p
+		SET p.is_banned = EXISTS (
+		  SELECT 1 FROM bans AS b
+		  WHERE b.player_id = p.id
+		  AND (b.expires_on IS NULL OR b.expires_on > CURRENT_TIMESTAMP)
+		)
+		WHERE p.is_banned = 1
+		OR p.id IN (SELECT player_id FROM bans)
+		"#,
+	)
+	.execute(pool)
+	.await?;
+
+	Ok(result.rows_affected())
+}
+
+/// Renames a map, e.g. when a new version gets released under a different name.
+pub async fn rename_map(map_id: u16, name: &str, pool: &Pool<MySql>) -> Eyre<u64> {
+	let result = rename_map_query(map_id, name)
+		.build()
+		.execute(pool)
+		.await?;
+
+	Ok(result.rows_affected())
+}
+
+fn rename_map_query(map_id: u16, name: &str) -> QueryBuilder<'static, MySql> {
+	let mut query = QueryBuilder::new("UPDATE maps SET name = ");
+	query
+		.push_bind(name.to_owned())
+		.push(", updated_on = CURRENT_TIMESTAMP WHERE id = ")
+		.push_bind(map_id);
+	query
+}
+
+pub type MapperData = (String, u32);
+/// Sets `created_by` for every `(map_name, mapper_id)` pair.
+pub async fn set_mappers(mappers: &[MapperData], pool: &Pool<MySql>) -> Eyre<u64> {
+	let mut transaction = pool.begin().await?;
+	let mut updated = 0;
+
+	for (map_name, mapper_id) in mappers {
+		updated += sqlx::query("UPDATE maps SET created_by = ? WHERE name = ?")
+			.bind(mapper_id)
+			.bind(map_name)
+			.execute(&mut transaction)
+			.await?
+			.rows_affected();
+	}
+
+	transaction.commit().await?;
+
+	Ok(updated)
+}
+
+pub async fn revoke_api_key(api_key: &str, pool: &Pool<MySql>) -> Eyre<u64> {
+	let result = sqlx::query(
+		r#"
+		UPDATE api_keys
+		SET revoked_on = CURRENT_TIMESTAMP
+		WHERE api_key = ?
+		AND revoked_on IS NULL
+		"#,
+	)
+	.bind(api_key)
+	.execute(pool)
+	.await?;
+
+	Ok(result.rows_affected())
+}
+
+/// Links an API key to a Twitch channel, creating the key's entry in `streamers` if necessary.
+pub async fn set_streamer_channel(
+	api_key: &str,
+	channel_name: &str,
+	pool: &Pool<MySql>,
+) -> Eyre<u64> {
+	let result = sqlx::query(
+		r#"
+		INSERT INTO streamers (api_key, channel_name)
+		VALUES (?, ?)
+		ON DUPLICATE KEY UPDATE channel_name = VALUES(channel_name)
+		"#,
+	)
+	.bind(api_key)
+	// channels are looked up in lowercase
+	.bind(channel_name.to_lowercase())
+	.execute(pool)
+	.await?;
+
+	Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{rename_map_query, set_ban_query};
+
+	#[test]
+	fn bans_are_bound() {
+		assert_eq!(set_ban_query(42, true).sql(), "UPDATE players SET is_banned = ? WHERE id = ?");
+	}
+
+	#[test]
+	fn renames_are_bound() {
+		let name = r#"kz_" WHERE 1=1; DROP TABLE maps; --"#;
+
+		assert_eq!(
+			rename_map_query(42, name).sql(),
+			"UPDATE maps SET name = ?, updated_on = CURRENT_TIMESTAMP WHERE id = ?"
+		);
+	}
+}
@@ -1,2 +1,3 @@
 pub mod crd;
+pub mod points;
 pub mod schemas;
@@ -0,0 +1,196 @@
+use {
+	crate::schemas::{CourseRow, RecordRow},
+	color_eyre::Result as Eyre,
+	gokz_rs::prelude::Mode,
+	sqlx::{MySql, Pool, QueryBuilder, Transaction},
+	std::collections::{HashMap, HashSet},
+};
+
+/// Points for finishing a course, indexed by tier.
+pub const TIER_POINTS: [u32; 8] = [0, 50, 100, 200, 350, 550, 800, 1100];
+
+/// Bonus points for the top 20 places of a leaderboard.
+pub const PLACEMENT_POINTS: [u32; 20] = [
+	1000, 800, 650, 550, 475, 425, 375, 325, 275, 225, 200, 175, 150, 125, 100, 80, 60, 40, 20, 10,
+];
+
+/// A single leaderboard, identified by `(course_id, mode_id, has_teleports)`.
+pub type Leaderboard = (u32, u8, bool);
+
+pub fn calculate(tier: u8, place: u32) -> u32 {
+	let tier_points = TIER_POINTS
+		.get(tier as usize)
+		.copied()
+		.unwrap_or(0);
+
+	let placement_points = place
+		.checked_sub(1)
+		.and_then(|idx| PLACEMENT_POINTS.get(idx as usize))
+		.copied()
+		.unwrap_or(0);
+
+	tier_points + placement_points
+}
+
+pub fn tier_for_mode(course: &CourseRow, mode_id: u8) -> u8 {
+	match Mode::try_from(mode_id) {
+		Ok(Mode::SimpleKZ) => course.skz_difficulty,
+		Ok(Mode::Vanilla) => course.vnl_difficulty,
+		_ => course.kzt_difficulty,
+	}
+}
+
+pub type PointsData = (u32, u8, bool, u32, u32, u32, u32);
+
+/// Leaderboards that need new points, mapped to the fastest time that was added, changed or removed
+/// on them.
+pub type Changes = HashMap<Leaderboard, f64>;
+
+/// Records that `time` changed on `leaderboard`.
+pub fn track(changes: &mut Changes, leaderboard: Leaderboard, time: f64) {
+	changes
+		.entry(leaderboard)
+		.and_modify(|fastest| *fastest = fastest.min(time))
+		.or_insert(time);
+}
+
+/// Recomputes places and points for every given leaderboard. Players whose PB is faster than the
+/// leaderboard's changed time keep their place, so only the rows below it are rescored.
+pub async fn recalculate(changes: &Changes, transaction: &mut Transaction<'_, MySql>) -> Eyre<()> {
+	for (&(course_id, mode_id, has_teleports), &fastest) in changes {
+		let course = sqlx::query_as::<_, CourseRow>("SELECT * FROM courses WHERE id = ?")
+			.bind(course_id)
+			.fetch_one(&mut *transaction)
+			.await?;
+
+		let tier = tier_for_mode(&course, mode_id);
+		let teleports = format!(" AND teleports {} 0", if has_teleports { ">" } else { "=" });
+
+		// nothing faster than `fastest` changed, so these players are still ahead of everyone else
+		let mut query =
+			QueryBuilder::new("SELECT COUNT(DISTINCT player_id) FROM records WHERE course_id = ");
+		query
+			.push_bind(course_id)
+			.push(" AND mode_id = ")
+			.push_bind(mode_id)
+			.push(&teleports)
+			.push(" AND time < ")
+			.push_bind(fastest);
+
+		let (unchanged,) = query
+			.build_query_as::<(i64,)>()
+			.fetch_one(&mut *transaction)
+			.await?;
+
+		let unchanged = unchanged as u32;
+
+		let mut query = QueryBuilder::new("SELECT * FROM records WHERE course_id = ");
+		query
+			.push_bind(course_id)
+			.push(" AND mode_id = ")
+			.push_bind(mode_id)
+			.push(&teleports)
+			.push(" AND time >= ")
+			.push_bind(fastest)
+			.push(" AND player_id NOT IN (SELECT player_id FROM records WHERE course_id = ")
+			.push_bind(course_id)
+			.push(" AND mode_id = ")
+			.push_bind(mode_id)
+			.push(&teleports)
+			.push(" AND time < ")
+			.push_bind(fastest)
+			.push(") ORDER BY time ASC, created_on ASC");
+
+		let records = query
+			.build_query_as::<RecordRow>()
+			.fetch_all(&mut *transaction)
+			.await?;
+
+		// records are sorted by time, so the first record of every player is their PB
+		let mut seen_players = HashSet::new();
+		let points = records
+			.into_iter()
+			.filter(|record| seen_players.insert(record.player_id))
+			.enumerate()
+			.map(|(i, record)| {
+				let place = unchanged + i as u32 + 1;
+				(
+					course_id,
+					mode_id,
+					has_teleports,
+					record.player_id,
+					record.id,
+					place,
+					calculate(tier, place),
+				)
+			})
+			.collect::<Vec<PointsData>>();
+
+		let mut query = QueryBuilder::new("DELETE FROM points WHERE course_id = ");
+		query
+			.push_bind(course_id)
+			.push(" AND mode_id = ")
+			.push_bind(mode_id)
+			.push(" AND has_teleports = ")
+			.push_bind(has_teleports)
+			.push(" AND place > ")
+			.push_bind(unchanged)
+			.build()
+			.execute(&mut *transaction)
+			.await?;
+
+		for chunk in points.chunks(1000) {
+			let mut query = QueryBuilder::new(
+				r#"
+				INSERT INTO points
+				  (course_id, mode_id, has_teleports, player_id, record_id, place, points)
+				"#,
+			);
+			query
+				.push_values(
+					chunk,
+					|mut query,
+					 (course_id, mode_id, has_teleports, player_id, record_id, place, points)| {
+						query
+							.push_bind(course_id)
+							.push_bind(mode_id)
+							.push_bind(has_teleports)
+							.push_bind(player_id)
+							.push_bind(record_id)
+							.push_bind(place)
+							.push_bind(points);
+					},
+				)
+				.build()
+				.execute(&mut *transaction)
+				.await?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Recomputes points for every leaderboard that has at least one record.
+pub async fn recalculate_all(pool: &Pool<MySql>) -> Eyre<usize> {
+	let changes = sqlx::query_as::<_, (u32, u8, bool)>(
+		r#"
+		SELECT DISTINCT
+		  course_id,
+		  mode_id,
+		  teleports > 0 AS has_teleports
+		FROM records
+		"#,
+	)
+	.fetch_all(pool)
+	.await?
+	.into_iter()
+	// every time is positive, so this rescores the whole leaderboard
+	.map(|leaderboard| (leaderboard, 0.0))
+	.collect::<Changes>();
+
+	let mut transaction = pool.begin().await?;
+	recalculate(&changes, &mut transaction).await?;
+	transaction.commit().await?;
+
+	Ok(changes.len())
+}
@@ -51,6 +51,14 @@ pub struct PlayerRow {
 	pub is_banned: bool,
 }
 
+#[derive(Debug, Clone, FromRow)]
+pub struct PlayerNameRow {
+	pub player_id: u32,
+	pub name: String,
+	pub first_seen: PrimitiveDateTime,
+	pub last_seen: PrimitiveDateTime,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FancyPlayer {
 	pub id: u32,
@@ -105,3 +113,46 @@ pub struct RecordRow {
 	pub teleports: u32,
 	pub created_on: PrimitiveDateTime,
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct BanRow {
+	pub id: u32,
+	pub player_id: u32,
+	pub ban_type: String,
+	pub reason: String,
+	pub server_id: u16,
+	pub created_on: PrimitiveDateTime,
+	/// `None` for permanent bans.
+	pub expires_on: Option<PrimitiveDateTime>,
+	pub updated_on: PrimitiveDateTime,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct PointsRow {
+	pub course_id: u32,
+	pub mode_id: u8,
+	pub has_teleports: bool,
+	pub player_id: u32,
+	pub record_id: u32,
+	pub place: u32,
+	pub points: u32,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ApiKeyRow {
+	pub api_key: String,
+	pub name: String,
+	/// Comma separated list of scopes, e.g. `read,twitch`.
+	pub scopes: String,
+	pub requests_per_minute: u32,
+	pub created_on: PrimitiveDateTime,
+	pub revoked_on: Option<PrimitiveDateTime>,
+}
+
+impl ApiKeyRow {
+	pub fn has_scope(&self, scope: &str) -> bool {
+		self.scopes
+			.split(',')
+			.any(|s| s.trim() == scope)
+	}
+}
@@ -0,0 +1,19 @@
+-- Existing keys keep working for the Twitch integration and get the default quota.
+ALTER TABLE api_keys
+  ADD COLUMN name VARCHAR(255) NOT NULL DEFAULT "twitch",
+  ADD COLUMN scopes VARCHAR(255) NOT NULL DEFAULT "read,twitch",
+  ADD COLUMN requests_per_minute INT UNSIGNED NOT NULL DEFAULT 60,
+  ADD COLUMN created_on DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  ADD COLUMN revoked_on DATETIME;
+
+ALTER TABLE api_keys
+  ALTER COLUMN name DROP DEFAULT,
+  ALTER COLUMN scopes SET DEFAULT "read";
+
+-- migrate:down
+ALTER TABLE api_keys
+  DROP COLUMN name,
+  DROP COLUMN scopes,
+  DROP COLUMN requests_per_minute,
+  DROP COLUMN created_on,
+  DROP COLUMN revoked_on;
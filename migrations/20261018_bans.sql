@@ -0,0 +1,17 @@
+CREATE TABLE
+  IF NOT EXISTS bans (
+    id INT UNSIGNED NOT NULL PRIMARY KEY,
+    player_id INT UNSIGNED NOT NULL,
+    ban_type VARCHAR(255) NOT NULL,
+    reason TEXT NOT NULL,
+    server_id SMALLINT UNSIGNED NOT NULL,
+    created_on DATETIME NOT NULL,
+    expires_on DATETIME,
+    updated_on DATETIME NOT NULL,
+    INDEX (player_id),
+    INDEX (created_on),
+    FOREIGN KEY (player_id) REFERENCES players (id)
+  );
+
+-- migrate:down
+DROP TABLE bans;
@@ -0,0 +1,8 @@
+ALTER TABLE players
+  ADD INDEX players_name (name),
+  ADD FULLTEXT players_name_fulltext (name);
+
+-- migrate:down
+ALTER TABLE players
+  DROP INDEX players_name,
+  DROP INDEX players_name_fulltext;
@@ -0,0 +1,24 @@
+CREATE TABLE
+  IF NOT EXISTS player_names (
+    player_id INT UNSIGNED NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    first_seen DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (player_id, name),
+    INDEX (name),
+    FOREIGN KEY (player_id) REFERENCES players (id)
+  );
+
+INSERT IGNORE INTO player_names
+  (player_id, name, first_seen, last_seen)
+SELECT
+  p.id,
+  p.name,
+  COALESCE(MIN(r.created_on), CURRENT_TIMESTAMP),
+  COALESCE(MAX(r.created_on), CURRENT_TIMESTAMP)
+FROM players AS p
+LEFT JOIN records AS r ON r.player_id = p.id
+GROUP BY p.id;
+
+-- migrate:down
+DROP TABLE player_names;
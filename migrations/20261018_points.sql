@@ -0,0 +1,19 @@
+CREATE TABLE
+  IF NOT EXISTS points (
+    course_id INT UNSIGNED NOT NULL,
+    mode_id TINYINT UNSIGNED NOT NULL,
+    has_teleports BOOLEAN NOT NULL,
+    player_id INT UNSIGNED NOT NULL,
+    record_id INT UNSIGNED NOT NULL,
+    place INT UNSIGNED NOT NULL,
+    points INT UNSIGNED NOT NULL,
+    PRIMARY KEY (course_id, mode_id, has_teleports, player_id),
+    INDEX (player_id, mode_id, has_teleports),
+    FOREIGN KEY (course_id) REFERENCES courses (id),
+    FOREIGN KEY (mode_id) REFERENCES modes (id),
+    FOREIGN KEY (player_id) REFERENCES players (id),
+    FOREIGN KEY (record_id) REFERENCES records (id)
+  );
+
+-- migrate:down
+DROP TABLE points;
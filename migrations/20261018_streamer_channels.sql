@@ -0,0 +1,7 @@
+ALTER TABLE streamers
+  ADD COLUMN channel_name VARCHAR(255),
+  ADD UNIQUE (channel_name);
+
+-- migrate:down
+ALTER TABLE streamers
+  DROP COLUMN channel_name;
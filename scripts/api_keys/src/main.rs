@@ -0,0 +1,158 @@
+#![deny(clippy::perf)]
+#![warn(clippy::suspicious, clippy::style)]
+
+use {
+	clap::{Parser, Subcommand},
+	color_eyre::{eyre::eyre, Result as Eyre},
+	database::crd::{
+		create::insert_api_key,
+		read::get_api_keys,
+		update::{revoke_api_key, set_streamer_channel},
+	},
+	log::info,
+	rand::{distributions::Alphanumeric, Rng},
+	serde::Deserialize,
+	sqlx::mysql::MySqlPoolOptions,
+	std::path::PathBuf,
+};
+
+#[derive(Debug, Parser)]
+struct Args {
+	/// What to do
+	#[command(subcommand)]
+	mode: Mode,
+
+	/// Config file containing a MySQL connection string
+	#[arg(short, long)]
+	#[clap(default_value = "./config.toml")]
+	config_file: PathBuf,
+
+	/// Print debug information
+	#[arg(long)]
+	#[clap(default_value = "false")]
+	debug: bool,
+}
+
+#[derive(Debug, Subcommand)]
+enum Mode {
+	/// Issue a new API key.
+	Issue {
+		/// Who the key belongs to
+		name: String,
+
+		/// Comma separated list of scopes (e.g. `read,twitch`)
+		#[arg(long)]
+		#[clap(default_value = "read")]
+		scopes: String,
+
+		/// How many requests the key may send per minute
+		#[arg(long)]
+		#[clap(default_value = "60")]
+		requests_per_minute: u32,
+
+		/// Twitch channel that gets updated with this key (needs the `twitch` scope)
+		#[arg(long)]
+		twitch_channel: Option<String>,
+	},
+	/// Link an existing API key to a Twitch channel.
+	Channel {
+		api_key: String,
+		channel_name: String,
+	},
+	/// Revoke an existing API key.
+	Revoke { api_key: String },
+	/// List all API keys.
+	List,
+}
+
+#[derive(Debug, Deserialize)]
+struct Config {
+	mysql_url: String,
+}
+
+#[tokio::main]
+async fn main() -> Eyre<()> {
+	color_eyre::install()?;
+	let args = Args::parse();
+	let config_file = std::fs::read_to_string(args.config_file)?;
+	let config: Config = toml::from_str(&config_file)?;
+
+	std::env::set_var("RUST_LOG", if args.debug { "DEBUG" } else { "api_keys=INFO" });
+	env_logger::init();
+
+	let pool = MySqlPoolOptions::new()
+		.connect(&config.mysql_url)
+		.await?;
+
+	match args.mode {
+		Mode::Issue {
+			name,
+			scopes,
+			requests_per_minute,
+			twitch_channel,
+		} => {
+			let api_key = rand::thread_rng()
+				.sample_iter(&Alphanumeric)
+				.take(32)
+				.map(char::from)
+				.collect::<String>();
+
+			let scopes = scopes
+				.split(',')
+				.map(str::trim)
+				.filter(|scope| !scope.is_empty())
+				.collect::<Vec<_>>()
+				.join(",");
+
+			insert_api_key(&(api_key.clone(), name.clone(), scopes, requests_per_minute), &pool)
+				.await?;
+
+			if let Some(channel_name) = twitch_channel {
+				set_streamer_channel(&api_key, &channel_name, &pool).await?;
+				info!("Linked API key to `{channel_name}`.");
+			}
+
+			info!("Issued API key for `{name}`.");
+			println!("{api_key}");
+		}
+		Mode::Channel {
+			api_key,
+			channel_name,
+		} => {
+			if !get_api_keys(&pool)
+				.await?
+				.iter()
+				.any(|key| key.api_key == api_key && key.revoked_on.is_none())
+			{
+				return Err(eyre!("No active API key `{api_key}`."));
+			}
+
+			set_streamer_channel(&api_key, &channel_name, &pool).await?;
+			info!("Linked API key `{api_key}` to `{channel_name}`.");
+		}
+		Mode::Revoke { api_key } => {
+			if revoke_api_key(&api_key, &pool).await? == 0 {
+				return Err(eyre!("No active API key `{api_key}`."));
+			}
+
+			info!("Revoked API key `{api_key}`.");
+		}
+		Mode::List => {
+			for api_key in get_api_keys(&pool).await? {
+				println!(
+					"{} | {} | scopes: {} | {}/min | created: {} | revoked: {}",
+					api_key.api_key,
+					api_key.name,
+					api_key.scopes,
+					api_key.requests_per_minute,
+					api_key.created_on,
+					api_key
+						.revoked_on
+						.map_or_else(|| String::from("no"), |revoked_on| revoked_on.to_string()),
+				);
+			}
+		}
+	}
+
+	Ok(())
+}
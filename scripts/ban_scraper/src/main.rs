@@ -2,10 +2,13 @@
 #![warn(clippy::suspicious, clippy::style)]
 
 use {
-	chrono::NaiveDateTime,
+	chrono::{DateTime, NaiveDateTime, TimeZone, Utc},
 	clap::Parser,
 	color_eyre::Result as Eyre,
-	database::schemas::steam_id64_to_account_id,
+	database::{
+		crd::create::{BanData, Conflict},
+		schemas::steam_id64_to_account_id,
+	},
 	gokz_rs::bans::Ban,
 	log::info,
 	serde::Deserialize,
@@ -49,46 +52,62 @@ async fn main() -> Eyre<()> {
 
 	let mut old_bans = HashSet::new();
 	loop {
-		let new_bans = client
+		let bans = client
 			.get("https://kztimerglobal.com/api/v2/bans?limit=100000")
 			.send()
 			.await?
 			.json::<Vec<Ban>>()
-			.await?
+			.await?;
+
+		let new_bans = bans
+			.iter()
+			.map(|ban| (ban.id, ban.updated_on.clone()))
+			.collect::<HashSet<_>>();
+
+		let mut players = Vec::new();
+		let changes = bans
 			.into_iter()
+			.filter(|ban| !old_bans.contains(&(ban.id, ban.updated_on.clone())))
 			.filter_map(|ban| {
-				let expiration_date =
-					NaiveDateTime::parse_from_str(&ban.expires_on, "%Y-%m-%dT%H:%M:%S").ok()?;
-				let creation_date =
-					NaiveDateTime::parse_from_str(&ban.created_on, "%Y-%m-%dT%H:%M:%S").ok()?;
-				let is_banned = expiration_date <= creation_date;
-
-				Some((ban.steamid64, is_banned))
+				let player_id = steam_id64_to_account_id(ban.steamid64.parse().ok()?).ok()?;
+				let created_on = parse_date(&ban.created_on)?;
+				let expires_on = parse_date(&ban.expires_on)?;
+				let updated_on = parse_date(&ban.updated_on)?;
+
+				// permanent bans "expire" before they were created
+				let expires_on = (expires_on > created_on).then_some(expires_on);
+
+				players.push((player_id, ban.player_name, 0));
+
+				Some((
+					ban.id as u32,
+					player_id,
+					ban.ban_type,
+					ban.notes,
+					ban.server_id as u16,
+					created_on,
+					expires_on,
+					updated_on,
+				))
 			})
-			.collect::<HashSet<_>>();
+			.collect::<Vec<BanData>>();
+
+		// Banned players don't necessarily have any records, so they might not be in the
+		// database yet.
+		database::crd::create::upsert_players(&players, Conflict::Skip, &pool).await?;
 
-		let changes = new_bans.difference(&old_bans);
-
-		for (steam_id64, is_banned) in changes {
-			let Ok(steam_id64) = steam_id64.parse::<u64>() else {
-				continue;
-			};
-			let Ok(player_id) = steam_id64_to_account_id(steam_id64) else {
-				continue;
-			};
-			let is_banned = *is_banned as u8;
-
-			_ = sqlx::query(&format!(
-				r#"
-				UPDATE IGNORE players
-				SET is_banned = {is_banned}
-				WHERE id = {player_id}
-				"#
-			))
-			.execute(&pool)
-			.await;
-
-			info!("Updated ban for `{player_id}` to `{is_banned}`.");
+		for chunk in changes.chunks(1000) {
+			database::crd::create::insert_bans(chunk, &pool).await?;
+		}
+
+		if !changes.is_empty() {
+			info!("Updated {} bans.", changes.len());
+		}
+
+		// bans can expire without any new data coming in
+		let updated = database::crd::update::update_ban_status(&pool).await?;
+		if updated > 0 {
+			info!("Updated ban status of {updated} players.");
 		}
 
 		old_bans = new_bans;
@@ -96,3 +115,9 @@ async fn main() -> Eyre<()> {
 		std::thread::sleep(Duration::from_secs(120));
 	}
 }
+
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+	NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S")
+		.ok()
+		.map(|date| Utc.from_utc_datetime(&date))
+}
@@ -6,7 +6,10 @@ use {
 	clap::{Parser, Subcommand},
 	color_eyre::Result as Eyre,
 	database::{
-		crd::read::{get_map, get_server},
+		crd::{
+			create::{Conflict, RecordData, UpsertCount},
+			read::{get_map, get_server},
+		},
 		schemas::steam_id64_to_account_id,
 	},
 	gokz_rs::{
@@ -14,9 +17,9 @@ use {
 		records::Record as GlobalRecord,
 		GlobalAPI,
 	},
-	log::info,
+	log::{info, warn},
 	serde::{Deserialize, Serialize},
-	sqlx::{mysql::MySqlPoolOptions, QueryBuilder},
+	sqlx::{mysql::MySqlPoolOptions, MySql, Pool},
 	std::{
 		path::PathBuf,
 		time::{Duration, Instant},
@@ -46,6 +49,9 @@ enum Mode {
 	InputFile { file: PathBuf },
 	/// Scrape records from the GlobalAPI and insert them into the database.
 	Scrape { start_id: Option<u32> },
+	/// Delete records (e.g. ones that got removed from the GlobalAPI) and recalculate the
+	/// leaderboards they were on.
+	Delete { record_ids: Vec<u32> },
 }
 
 #[derive(Debug, Deserialize)]
@@ -76,6 +82,7 @@ async fn main() -> Eyre<()> {
 			match serde_json::from_str::<Vec<ElasticRecord>>(&file) {
 				Ok(elastic_records) => {
 					let mut records = Vec::new();
+					let mut player_names = Vec::new();
 					for record in elastic_records {
 						let map = get_map(MapIdentifier::Name(record.map_name), &pool).await?;
 						let course_id = (map.id * 100) as u32 + record.stage as u32;
@@ -94,30 +101,26 @@ async fn main() -> Eyre<()> {
 							Utc,
 						);
 
-						if database::crd::read::get_record(record.id, &pool)
-							.await
-							.is_err()
-						{
-							records.push((
-								record.id,
-								course_id,
-								mode as u8,
-								player_id,
-								server.id,
-								record.time,
-								record.teleports,
-								created_on,
-							));
-						} else {
-							info!("Skipping `{}`.", record.id);
-						}
+						records.push((
+							record.id,
+							course_id,
+							mode as u8,
+							player_id,
+							server.id,
+							record.time,
+							record.teleports,
+							created_on,
+						));
+						player_names.push((player_id, record.player_name, created_on));
 					}
 
-					database::crd::create::insert_records(&records, &pool).await?;
+					insert_records(&records, &pool).await?;
+					database::crd::create::insert_player_names(&player_names, &pool).await?;
 				}
 				_ => match serde_json::from_str::<Vec<GlobalRecord>>(&file) {
 					Ok(global_records) => {
 						let mut records = Vec::new();
+						let mut player_names = Vec::new();
 						for record in global_records {
 							let record_id = record.id as u32;
 							let course_id = ((record.map_id * 100) + record.stage) as u32;
@@ -136,20 +139,17 @@ async fn main() -> Eyre<()> {
 								Utc,
 							);
 
-							if database::crd::read::get_record(record_id, &pool)
-								.await
-								.is_err()
-							{
-								records.push((
-									record_id, course_id, mode_id, player_id, server_id, time,
-									teleports, created_on,
-								));
-							} else {
-								info!("Skipping `{record_id}`.");
+							records.push((
+								record_id, course_id, mode_id, player_id, server_id, time,
+								teleports, created_on,
+							));
+							if let Some(player_name) = record.player_name {
+								player_names.push((player_id, player_name, created_on));
 							}
 						}
 
-						database::crd::create::insert_records(&records, &pool).await?;
+						insert_records(&records, &pool).await?;
+						database::crd::create::insert_player_names(&player_names, &pool).await?;
 					}
 					_ => panic!("Invalid input format."),
 				},
@@ -194,61 +194,71 @@ async fn main() -> Eyre<()> {
 					Utc,
 				);
 
-				// If the player isn't in DB, insert them before inserting the record.
-				if let Ok(player) =
-					database::crd::read::get_player(PlayerIdentifier::SteamID64(steam_id64), &pool)
-						.await
-				{
-					if let Some(player_name) = record.player_name {
-						if player_name != player.name {
-							let mut query = QueryBuilder::new("UPDATE players SET name = ");
-							query
-								.push_bind(player_name)
-								.push(" WHERE id = ")
-								.push_bind(player.id);
-							query.build().execute(&pool).await?;
-						}
-					}
-				} else {
-					database::crd::create::insert_players(
-						&[(
-							player_id,
-							record
-								.player_name
-								.unwrap_or_else(|| String::from("unknown")),
-							0,
-						)],
+				// Insert the player if they aren't in the DB yet, or update their name if it changed.
+				let (player_name, conflict) = match &record.player_name {
+					Some(player_name) => (player_name.clone(), Conflict::Overwrite),
+					None => (String::from("unknown"), Conflict::Skip),
+				};
+				database::crd::create::upsert_players(
+					&[(player_id, player_name, 0)],
+					conflict,
+					&pool,
+				)
+				.await?;
+
+				// Keep track of old names so they can still be looked up after a rename.
+				if let Some(player_name) = record.player_name {
+					database::crd::create::insert_player_names(
+						&[(player_id, player_name, created_on)],
 						&pool,
 					)
 					.await?;
 				}
 
-				if database::crd::read::get_server(record.server_id.to_string(), &pool)
+				// Only ask the GlobalAPI about servers we don't know yet.
+				if get_server(record.server_id.to_string(), &pool)
 					.await
 					.is_err()
 				{
 					let server = GlobalAPI::get_server_by_id(record.server_id, &client).await?;
-					database::crd::create::insert_servers(
+					database::crd::create::upsert_servers(
 						&[(
 							server.id as u16,
 							server.name,
 							steam_id64_to_account_id(server.owner_steamid64.parse()?)?,
 							0,
 						)],
+						Conflict::Skip,
 						&pool,
 					)
 					.await?;
 				}
 
-				database::crd::create::insert_records(
+				// `start_id` might overlap with records we already have
+				let UpsertCount { inserted, .. } = database::crd::create::upsert_records(
 					&[(
 						record_id, course_id, mode_id, player_id, server_id, time, teleports,
 						created_on,
 					)],
+					Conflict::Skip,
 					&pool,
 				)
 				.await?;
-				info!("Inserted record `{record_id}`.");
+
+				if inserted > 0 {
+					info!("Inserted record `{record_id}`.");
+				} else {
+					info!("Skipped existing record `{record_id}`.");
+				}
+			}
+		}
+		Mode::Delete { record_ids } => {
+			for record_id in record_ids {
+				if database::crd::delete::delete_record(record_id, &pool).await? {
+					info!("Deleted record `{record_id}`.");
+				} else {
+					warn!("Record `{record_id}` doesn't exist.");
+				}
 			}
 		}
 	};
@@ -261,6 +271,17 @@ async fn main() -> Eyre<()> {
 	Ok(())
 }
 
+/// Inserts `records`, skipping the ones that are already in the database.
+async fn insert_records(records: &[RecordData], pool: &Pool<MySql>) -> Eyre<()> {
+	let UpsertCount {
+		inserted, skipped, ..
+	} = database::crd::create::upsert_records(records, Conflict::Skip, pool).await?;
+
+	info!("Inserted {inserted} records, skipped {skipped} existing ones.");
+
+	Ok(())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ElasticRecord {
 	mode: String,